@@ -0,0 +1,63 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use crate::util::parse_num;
+
+/// A named region of the input from an annotation file, like a section of an executable
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub start: usize,
+    /// Last address of the region, or `None` for one that goes on until the next
+    pub end: Option<usize>,
+    pub name: String,
+}
+
+/// Reads the regions of an annotation file, sorted by where they start. Its lines are like
+/// `0x1000-0x1fff .text`, with both ends of the range included, or `0x1000 .text` for a region
+/// that goes on until the next one. Lines starting with `#` are comments
+pub fn load(path: &Path) -> io::Result<Vec<Region>> {
+    let text = fs::read_to_string(path)?;
+    let invalid = |index: usize, message: String| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            format!("{}: {message} on line {}", path.display(), index + 1),
+        )
+    };
+    let address = |text: &str| parse_num(text).map_err(|_| format!("Invalid address \"{text}\""));
+
+    let mut regions = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (range, name) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| invalid(index, "Expected an address and a name".to_string()))?;
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (start, Some(end)),
+            None => (range, None),
+        };
+        let start = address(start).map_err(|e| invalid(index, e))?;
+        let end = end
+            .map(address)
+            .transpose()
+            .map_err(|e| invalid(index, e))?;
+        if end.is_some_and(|end| end < start) {
+            return Err(invalid(
+                index,
+                format!("Region {range} ends before it starts"),
+            ));
+        }
+        regions.push(Region {
+            start,
+            end,
+            name: name.trim().to_string(),
+        });
+    }
+    regions.sort_by_key(|region| region.start);
+    Ok(regions)
+}
@@ -0,0 +1,216 @@
+use std::io::{self, BufRead, Read};
+
+use crate::inflate::Inflate;
+
+const ZIP_LOCAL_HEADER: &[u8] = b"PK\x03\x04";
+const ZIP_FLAG_DATA_DESCRIPTOR: u16 = 0x08;
+const ZIP_STORED: u16 = 0;
+const ZIP_DEFLATED: u16 = 8;
+
+const TAR_BLOCK: usize = 512;
+const TAR_MAGIC: &[u8] = b"ustar";
+const TAR_MAGIC_OFFSET: usize = 257;
+
+fn not_found(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No member named \"{name}\" in archive"),
+    )
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid archive: {message}"),
+    )
+}
+
+/// Archive names are often stored with a leading "./"
+fn same_name(stored: &str, wanted: &str) -> bool {
+    stored.trim_start_matches("./") == wanted.trim_start_matches("./")
+}
+
+fn skip(reader: &mut impl Read, count: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(count), &mut io::sink())?;
+    match skipped == count {
+        true => Ok(()),
+        false => Err(io::ErrorKind::UnexpectedEof.into()),
+    }
+}
+
+/// Finds the member called `name` in a zip or tar archive, returning a reader
+/// over its contents and its size when the archive records it
+pub fn open_member<R: BufRead + 'static>(
+    mut reader: R,
+    name: &str,
+) -> io::Result<(Box<dyn Read>, Option<u64>)> {
+    let header = reader.fill_buf()?;
+    if header.starts_with(ZIP_LOCAL_HEADER) {
+        open_zip_member(reader, name)
+    } else if header.len() >= TAR_BLOCK && is_tar_header(&header[..TAR_BLOCK]) {
+        open_tar_member(reader, name)
+    } else {
+        Err(invalid("not a zip or tar archive"))
+    }
+}
+
+fn is_tar_header(block: &[u8]) -> bool {
+    if block[TAR_MAGIC_OFFSET..].starts_with(TAR_MAGIC) {
+        return true;
+    }
+    // Pre-POSIX archives have no magic, but still carry a header checksum
+    let expected = parse_octal(&block[148..156]);
+    let actual: u64 = block
+        .iter()
+        .enumerate()
+        .map(|(index, &byte)| match index {
+            148..156 => b' ' as u64,
+            _ => byte as u64,
+        })
+        .sum();
+    expected == Some(actual)
+}
+
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(field).ok()?;
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    u64::from_str_radix(text, 8).ok()
+}
+
+fn field_str(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn open_tar_member<R: BufRead + 'static>(
+    mut reader: R,
+    name: &str,
+) -> io::Result<(Box<dyn Read>, Option<u64>)> {
+    let mut block = [0u8; TAR_BLOCK];
+    // Set by GNU long name entries and pax headers for the entry that follows them
+    let mut long_name: Option<String> = None;
+
+    loop {
+        if reader.fill_buf()?.is_empty() {
+            return Err(not_found(name));
+        }
+        reader.read_exact(&mut block)?;
+        if block.iter().all(|&b| b == 0) {
+            // End of archive marker
+            return Err(not_found(name));
+        }
+
+        let size = parse_octal(&block[124..136]).ok_or_else(|| invalid("bad tar entry size"))?;
+        let padded_size = size.div_ceil(TAR_BLOCK as u64) * TAR_BLOCK as u64;
+        let entry_type = block[156];
+
+        match entry_type {
+            b'L' | b'x' => {
+                let mut data = vec![0u8; size as usize];
+                reader.read_exact(&mut data)?;
+                skip(&mut reader, padded_size - size)?;
+                long_name = match entry_type {
+                    b'L' => Some(field_str(&data)),
+                    _ => pax_path(&data).or(long_name),
+                };
+                continue;
+            }
+            _ => {}
+        }
+
+        let entry_name = long_name.take().unwrap_or_else(|| {
+            let prefix = field_str(&block[345..500]);
+            let base = field_str(&block[..100]);
+            match prefix.is_empty() || !block[TAR_MAGIC_OFFSET..].starts_with(TAR_MAGIC) {
+                true => base,
+                false => format!("{prefix}/{base}"),
+            }
+        });
+
+        // Regular files use '0', or NUL in old archives
+        if matches!(entry_type, b'0' | 0) && same_name(&entry_name, name) {
+            return Ok((Box::new(reader.take(size)), Some(size)));
+        }
+        skip(&mut reader, padded_size)?;
+    }
+}
+
+/// Pulls the `path` record out of a pax extended header
+fn pax_path(data: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(data).lines().find_map(|record| {
+        let (_, keyword_value) = record.split_once(' ')?;
+        keyword_value.strip_prefix("path=").map(str::to_string)
+    })
+}
+
+fn open_zip_member<R: BufRead + 'static>(
+    mut reader: R,
+    name: &str,
+) -> io::Result<(Box<dyn Read>, Option<u64>)> {
+    let mut header = [0u8; 30];
+
+    loop {
+        reader.read_exact(&mut header[..4])?;
+        if &header[..4] != ZIP_LOCAL_HEADER {
+            // Reached the central directory without finding the member
+            return Err(not_found(name));
+        }
+        reader.read_exact(&mut header[4..])?;
+
+        let u16_at = |offset: usize| u16::from_le_bytes([header[offset], header[offset + 1]]);
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes(header[offset..offset + 4].try_into().expect("4 bytes"))
+        };
+        let flags = u16_at(6);
+        let method = u16_at(8);
+        let compressed_size = u32_at(18) as u64;
+        let size = u32_at(22) as u64;
+        let name_len = u16_at(26) as usize;
+        let extra_len = u16_at(28) as u64;
+
+        let mut entry_name = vec![0u8; name_len];
+        reader.read_exact(&mut entry_name)?;
+        skip(&mut reader, extra_len)?;
+        let entry_name = String::from_utf8_lossy(&entry_name);
+
+        // Sizes are only known up front when they aren't deferred to a data descriptor
+        let has_descriptor = flags & ZIP_FLAG_DATA_DESCRIPTOR != 0;
+        let known_size = (!has_descriptor).then_some(size);
+
+        if same_name(&entry_name, name) {
+            return match method {
+                ZIP_STORED if has_descriptor => {
+                    Err(invalid("stored member without a recorded size"))
+                }
+                ZIP_STORED => Ok((Box::new(reader.take(compressed_size)), known_size)),
+                ZIP_DEFLATED if has_descriptor => Ok((Box::new(Inflate::new(reader)), None)),
+                ZIP_DEFLATED => Ok((
+                    Box::new(Inflate::new(reader.take(compressed_size))),
+                    known_size,
+                )),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("Unsupported zip compression method {method}"),
+                )),
+            };
+        }
+
+        match (has_descriptor, method) {
+            (false, _) => skip(&mut reader, compressed_size)?,
+            (true, ZIP_DEFLATED) => {
+                // The only way to find the end of the data is to decompress it
+                let mut inflate = Inflate::new(reader);
+                io::copy(&mut inflate, &mut io::sink())?;
+                reader = inflate.into_inner();
+                skip_data_descriptor(&mut reader)?;
+            }
+            (true, _) => return Err(invalid("stored member without a recorded size")),
+        }
+    }
+}
+
+/// Skips the crc and sizes following deflated data, with or without their optional signature
+fn skip_data_descriptor(reader: &mut impl BufRead) -> io::Result<()> {
+    let has_signature = reader.fill_buf()?.starts_with(b"PK\x07\x08");
+    skip(reader, if has_signature { 16 } else { 12 })
+}
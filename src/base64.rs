@@ -0,0 +1,75 @@
+use std::io;
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid base64: {message}"),
+    )
+}
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes `bytes` as padded base64 in the standard alphabet
+pub fn encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let bits = group.iter().enumerate().fold(0u32, |bits, (index, &byte)| {
+            bits | (byte as u32) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            match index <= group.len() {
+                true => text.push(ALPHABET[(bits >> (18 - 6 * index) & 0x3f) as usize] as char),
+                false => text.push('='),
+            }
+        }
+    }
+    text
+}
+
+/// Value of a base64 digit, accepting both the standard and the URL safe alphabet
+fn digit_value(digit: u8) -> Option<u32> {
+    match digit {
+        b'A'..=b'Z' => Some((digit - b'A') as u32),
+        b'a'..=b'z' => Some((digit - b'a') as u32 + 26),
+        b'0'..=b'9' => Some((digit - b'0') as u32 + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes base64 text, ignoring the line breaks and other whitespace of wrapped (MIME) input
+pub fn decode(text: &[u8]) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+    let mut padded = false;
+
+    for (offset, &digit) in text.iter().enumerate() {
+        if digit.is_ascii_whitespace() {
+            continue;
+        }
+        if digit == b'=' {
+            padded = true;
+            continue;
+        }
+        let value = digit_value(digit)
+            .ok_or_else(|| invalid(format!("unexpected {:?} at offset {offset}", digit as char)))?;
+        if padded {
+            return Err(invalid(format!("data after padding at offset {offset}")));
+        }
+
+        bits = bits << 6 | value;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+        }
+    }
+
+    // A single leftover digit can't encode a whole byte
+    match bit_count {
+        6 => Err(invalid("truncated input".to_string())),
+        _ => Ok(bytes),
+    }
+}
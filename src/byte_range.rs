@@ -3,7 +3,15 @@ use std::str::FromStr;
 #[derive(Debug, Clone, Default)]
 pub struct ByteRange {
     pub start: usize,
-    pub end: usize,
+    /// End of the range, or `None` for one that goes on to the end of the input
+    pub end: Option<usize>,
+}
+
+impl ByteRange {
+    /// Whether the range ends before `addr`
+    pub fn ends_by(&self, addr: usize) -> bool {
+        self.end.is_some_and(|end| end <= addr)
+    }
 }
 
 impl FromStr for ByteRange {
@@ -11,20 +19,43 @@ impl FromStr for ByteRange {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts: Vec<&str> = s.split('-').collect();
-        if parts.len() != 2 {
-            return Err("Range must be in the format 'start-end'");
+        if parts.len() != 2 || parts.iter().all(|part| part.is_empty()) {
+            return Err("Range must be in the format 'start-end', 'start-' or '-end'");
         }
 
-        let start = usize::from_str_radix(parts[0].trim_start_matches("0x"), 16)
-            .or_else(|_| usize::from_str(parts[0]));
-        let end = usize::from_str_radix(parts[1].trim_start_matches("0x"), 16)
-            .or_else(|_| usize::from_str(parts[1]));
+        // Either side can be left out, for a range from the start or to the end of the input
+        let parse = |part: &str| match part {
+            "" => Ok(None),
+            _ => usize::from_str_radix(part.trim_start_matches("0x"), 16)
+                .or_else(|_| usize::from_str(part))
+                .map(Some),
+        };
 
-        let (start, end) = match (start, end) {
-            (Ok(s), Ok(e)) => (s, e),
+        let (start, end) = match (parse(parts[0]), parse(parts[1])) {
+            (Ok(s), Ok(e)) => (s.unwrap_or(0), e),
             _ => return Err("Range entries must either be in the format '0xFF' or '255'"),
         };
 
+        if end.is_some_and(|end| end < start) {
+            return Err("Range must not end before it starts");
+        }
+
         Ok(ByteRange { start, end })
     }
 }
+
+/// `ranges` in order, with those that overlap or touch joined into one
+pub fn merged(ranges: &[ByteRange]) -> Vec<ByteRange> {
+    let mut sorted = ranges.to_vec();
+    sorted.sort_by_key(|range| range.start);
+    let mut merged: Vec<ByteRange> = Vec::new();
+    for range in sorted {
+        match merged.last_mut() {
+            Some(last) if last.end.is_none_or(|end| range.start <= end) => {
+                last.end = last.end.zip(range.end).map(|(end, other)| end.max(other));
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
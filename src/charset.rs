@@ -0,0 +1,399 @@
+use std::ops::Range;
+
+use crate::codepage::{self, *};
+
+/// Whether `c` is an invisible character that only steers text, like a soft hyphen
+pub fn is_format(c: char) -> bool {
+    matches!(
+        c,
+        '\u{ad}' | '\u{200b}'..='\u{200f}' | '\u{2028}'..='\u{202e}' | '\u{2060}'..='\u{2064}' | '\u{feff}'
+    )
+}
+
+/// Whether `c` is a mark that goes on the character before it, like accents and the Arabic
+/// and Thai vowel marks, rather than taking a column of its own
+pub fn is_combining(c: char) -> bool {
+    matches!(
+        c,
+        '\u{300}'..='\u{36f}'
+            | '\u{64b}'..='\u{652}'
+            | '\u{e31}'
+            | '\u{e34}'..='\u{e3a}'
+            | '\u{e47}'..='\u{e4e}'
+            | '\u{1ab0}'..='\u{1aff}'
+            | '\u{1dc0}'..='\u{1dff}'
+            | '\u{20d0}'..='\u{20ff}'
+            | '\u{fe00}'..='\u{fe0f}'
+            | '\u{fe20}'..='\u{fe2f}'
+    )
+}
+
+/// Whether `c` takes two columns of a terminal, as CJK characters and most emoji do
+pub fn is_wide(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{115f}'
+            | '\u{2e80}'..='\u{303e}'
+            | '\u{3041}'..='\u{33ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{a000}'..='\u{a4cf}'
+            | '\u{ac00}'..='\u{d7a3}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{fe30}'..='\u{fe4f}'
+            | '\u{ff00}'..='\u{ff60}'
+            | '\u{ffe0}'..='\u{ffe6}'
+            | '\u{1f300}'..='\u{1f64f}'
+            | '\u{1f900}'..='\u{1f9ff}'
+            | '\u{20000}'..='\u{3fffd}'
+    )
+}
+
+/// Number of terminal columns `text` takes
+pub fn width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            c if is_combining(c) || is_format(c) => 0,
+            c if is_wide(c) => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Most bytes a character takes in any of the character sets
+pub const MAX_LEN: usize = 4;
+
+/// How a byte of text in a multibyte character set shows in the text column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextByte {
+    /// A byte that is a character of its own, or not part of a valid one
+    Byte(u8),
+    /// The first byte of a character, which it is shown on
+    Char(char),
+    /// Any other byte of a character, even one on the line after that of the first
+    Part,
+}
+
+/// Decodes the bytes of a line and, for a character it ends in the middle of, those after it
+struct Decoder {
+    data: Vec<u8>,
+    len: usize,
+    more: bool,
+    decoded: Vec<TextByte>,
+}
+
+impl Decoder {
+    /// Starts with `carry`, how the first bytes of the line were decoded along with the last.
+    /// `more` is whether bytes can follow those of `next`
+    fn new(carry: &[TextByte], bytes: &[u8], next: &[u8], more: bool) -> Self {
+        Decoder {
+            data: [bytes, next].concat(),
+            len: bytes.len(),
+            more,
+            decoded: carry.to_vec(),
+        }
+    }
+
+    /// Index of the next byte to decode, if it is on the line
+    fn index(&self) -> Option<usize> {
+        Some(self.decoded.len()).filter(|&index| index < self.len)
+    }
+
+    /// Whether the character at `index` runs past the bytes known, as `len` bytes, and can
+    /// only be decoded once more of them are
+    fn cut(&self, index: usize, len: usize) -> bool {
+        self.more && index + len > self.data.len()
+    }
+
+    /// The bytes of `range` as the character `c`
+    fn char(&mut self, range: Range<usize>, c: char) {
+        self.decoded.push(TextByte::Char(c));
+        self.decoded
+            .extend((range.start + 1..range.end).map(|_| TextByte::Part));
+    }
+
+    /// The byte at `index`, standing for itself
+    fn byte(&mut self, index: usize) {
+        self.decoded.push(TextByte::Byte(self.data[index]));
+    }
+
+    /// The bytes of the line, leaving in `carry` those after it that its last character took
+    fn finish(mut self, carry: &mut Vec<TextByte>) -> Vec<TextByte> {
+        *carry = self.decoded.split_off(self.len.min(self.decoded.len()));
+        self.decoded
+    }
+}
+
+/// Number of bytes of the UTF-8 sequence starting with `lead`, if it can start one
+fn utf8_len(lead: u8) -> Option<usize> {
+    match lead {
+        0xc2..=0xdf => Some(2),
+        0xe0..=0xef => Some(3),
+        0xf0..=0xf4 => Some(4),
+        _ => None,
+    }
+}
+
+/// Decodes a line of UTF-8, with `next` the bytes after it, as far as they are known and
+/// `more` if others can follow. `carry` holds how the first bytes of the line were decoded
+/// with the character the last one ended in, and is left holding that for the next line.
+/// Nothing is decoded if the line ends in a character whose end isn't known yet
+pub fn decode_utf8(
+    carry: &mut Vec<TextByte>,
+    bytes: &[u8],
+    next: &[u8],
+    more: bool,
+) -> Option<Vec<TextByte>> {
+    let mut decoder = Decoder::new(carry, bytes, next, more);
+    while let Some(index) = decoder.index() {
+        let Some(len) = utf8_len(decoder.data[index]) else {
+            decoder.byte(index);
+            continue;
+        };
+        let sequence = &decoder.data[index..(index + len).min(decoder.data.len())];
+        let continued = sequence[1..].iter().all(|&byte| byte & 0xc0 == 0x80);
+        if continued && decoder.cut(index, len) {
+            return None;
+        }
+        match std::str::from_utf8(sequence)
+            .ok()
+            .and_then(|text| text.chars().next())
+        {
+            Some(c) => decoder.char(index..index + len, c),
+            None => decoder.byte(index),
+        }
+    }
+    Some(decoder.finish(carry))
+}
+
+/// Decodes a line of UTF-16 like `decode_utf8`, in units of two bytes from the start of the
+/// dump, with the big end first if `big_endian`
+pub fn decode_utf16(
+    carry: &mut Vec<TextByte>,
+    bytes: &[u8],
+    next: &[u8],
+    more: bool,
+    big_endian: bool,
+) -> Option<Vec<TextByte>> {
+    let mut decoder = Decoder::new(carry, bytes, next, more);
+    let unit = |data: &[u8], index: usize| {
+        let pair = [data[index], data[index + 1]];
+        match big_endian {
+            true => u16::from_be_bytes(pair),
+            false => u16::from_le_bytes(pair),
+        }
+    };
+    while let Some(index) = decoder.index() {
+        if decoder.cut(index, 2) {
+            return None;
+        }
+        // A byte left over at the end of the input
+        if index + 2 > decoder.data.len() {
+            decoder.byte(index);
+            continue;
+        }
+        let first = unit(&decoder.data, index);
+        // A character past the first 65536 takes a pair of surrogates
+        let units = match first {
+            0xd800..=0xdbff if decoder.cut(index, 4) => return None,
+            0xd800..=0xdbff if index + 4 <= decoder.data.len() => {
+                vec![first, unit(&decoder.data, index + 2)]
+            }
+            _ => vec![first],
+        };
+        match char::decode_utf16(units.iter().copied()).next() {
+            Some(Ok(c)) => decoder.char(index..index + 2 * units.len(), c),
+            // A lone surrogate is only its own unit
+            _ => {
+                decoder.byte(index);
+                decoder.byte(index + 1);
+            }
+        }
+    }
+    Some(decoder.finish(carry))
+}
+
+/// Decodes a line of Shift-JIS, GBK or Big5 like `decode_utf8`, where characters past ASCII
+/// mostly take two bytes
+pub fn decode_double(
+    carry: &mut Vec<TextByte>,
+    bytes: &[u8],
+    next: &[u8],
+    more: bool,
+    charset: Charset,
+) -> Option<Vec<TextByte>> {
+    let mut decoder = Decoder::new(carry, bytes, next, more);
+    while let Some(index) = decoder.index() {
+        let lead = decoder.data[index];
+        // Bytes of their own, like ASCII, are decoded one by one
+        if !charset.is_lead(lead) {
+            decoder.byte(index);
+            continue;
+        }
+        if decoder.cut(index, 2) {
+            return None;
+        }
+        match decoder
+            .data
+            .get(index + 1)
+            .and_then(|&trail| charset.double(lead, trail))
+        {
+            Some(c) => decoder.char(index..index + 2, c),
+            None => decoder.byte(index),
+        }
+    }
+    Some(decoder.finish(carry))
+}
+
+/// Character set the text column is decoded in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Charset {
+    /// ASCII, with glyphs for control characters and `×` for every byte past it
+    Ascii,
+    /// Code page 437 of the IBM PC, whose accents, box drawing and greek letters fill
+    /// the bytes past ASCII in DOS programs and BIOS images
+    Cp437,
+    /// UTF-8, each character shown on its first byte and the others of it dimmed
+    Utf8,
+    /// UTF-16 with the little end first, as in the wide strings of Windows and .NET
+    Utf16le,
+    /// UTF-16 with the big end first, as in Java class files
+    Utf16be,
+    /// Shift-JIS as in code page 932 of Windows, for Japanese
+    #[value(name = "shift-jis", alias = "sjis")]
+    ShiftJis,
+    /// GBK, for Simplified Chinese
+    Gbk,
+    /// Big5, for Traditional Chinese
+    Big5,
+    /// EBCDIC as in code page 37, the one of US and Canadian IBM mainframes and AS/400s
+    Ebcdic,
+    /// Latin-1, as in ISO 8859-1, for Western European languages
+    #[value(alias = "iso8859-1")]
+    Latin1,
+    /// KOI8-R, for Russian
+    #[value(alias = "koi8-r")]
+    Koi8r,
+    /// ISO 8859-2, Latin-2, for Central and Eastern European languages
+    #[value(name = "iso8859-2")]
+    Iso8859_2,
+    /// ISO 8859-3, Latin-3, for Maltese and Esperanto
+    #[value(name = "iso8859-3")]
+    Iso8859_3,
+    /// ISO 8859-4, Latin-4, for the Baltic languages, before Latin-6 and -7
+    #[value(name = "iso8859-4")]
+    Iso8859_4,
+    /// ISO 8859-5, for Cyrillic
+    #[value(name = "iso8859-5")]
+    Iso8859_5,
+    /// ISO 8859-6, for Arabic
+    #[value(name = "iso8859-6")]
+    Iso8859_6,
+    /// ISO 8859-7, for Greek
+    #[value(name = "iso8859-7")]
+    Iso8859_7,
+    /// ISO 8859-8, for Hebrew
+    #[value(name = "iso8859-8")]
+    Iso8859_8,
+    /// ISO 8859-9, Latin-5, Latin-1 with Turkish letters
+    #[value(name = "iso8859-9")]
+    Iso8859_9,
+    /// ISO 8859-10, Latin-6, for the Nordic languages
+    #[value(name = "iso8859-10")]
+    Iso8859_10,
+    /// ISO 8859-11, for Thai
+    #[value(name = "iso8859-11")]
+    Iso8859_11,
+    /// ISO 8859-13, Latin-7, for the Baltic languages
+    #[value(name = "iso8859-13")]
+    Iso8859_13,
+    /// ISO 8859-14, Latin-8, for the Celtic languages
+    #[value(name = "iso8859-14")]
+    Iso8859_14,
+    /// ISO 8859-15, Latin-9, Latin-1 with the euro sign
+    #[value(name = "iso8859-15")]
+    Iso8859_15,
+    /// ISO 8859-16, Latin-10, for South-Eastern European languages
+    #[value(name = "iso8859-16")]
+    Iso8859_16,
+}
+
+impl Charset {
+    /// Character a byte stands for, if the character set has one for it
+    pub fn decode(self, byte: u8) -> Option<char> {
+        match self {
+            // Characters of more than a byte are only decoded along with the others of a line
+            Charset::Ascii | Charset::Utf8 | Charset::Utf16le | Charset::Utf16be => {
+                byte.is_ascii().then_some(byte as char)
+            }
+            Charset::Cp437 => Some(match byte {
+                ..0x80 => byte as char,
+                _ => CP437_HIGH[byte as usize - 0x80],
+            }),
+            Charset::Ebcdic => Some(EBCDIC[byte as usize]),
+            // Half width katakana, the only characters of a byte past ASCII
+            Charset::ShiftJis => match byte {
+                ..0x80 => Some(byte as char),
+                0xa1..=0xdf => char::from_u32(0xff61 + (byte - 0xa1) as u32),
+                _ => None,
+            },
+            Charset::Gbk => match byte {
+                ..0x80 => Some(byte as char),
+                0x80 => Some('€'),
+                _ => None,
+            },
+            Charset::Big5 => byte.is_ascii().then_some(byte as char),
+            Charset::Latin1 => Some(byte as char),
+            Charset::Koi8r => Some(match byte {
+                ..0x80 => byte as char,
+                _ => KOI8_R_HIGH[byte as usize - 0x80],
+            }),
+            _ => match (byte, self.iso8859_high()) {
+                (0xa0.., Some(high)) => Some(high[byte as usize - 0xa0]).filter(|&c| c != '\0'),
+                _ => Some(byte as char),
+            },
+        }
+    }
+
+    /// Whether `byte` starts a character of two bytes
+    fn is_lead(self, byte: u8) -> bool {
+        match self {
+            Charset::ShiftJis => matches!(byte, 0x81..=0x9f | 0xe0..=0xfc),
+            Charset::Gbk | Charset::Big5 => matches!(byte, 0x81..=0xfe),
+            _ => false,
+        }
+    }
+
+    /// Character of a pair of bytes, for the charsets with characters of two bytes
+    fn double(self, lead: u8, trail: u8) -> Option<char> {
+        let table = match self {
+            Charset::ShiftJis => SHIFT_JIS_DOUBLE,
+            Charset::Gbk => GBK_DOUBLE,
+            Charset::Big5 => BIG5_DOUBLE,
+            _ => return None,
+        };
+        codepage::double(table, lead, trail)
+    }
+
+    /// Bytes 0xa0 to 0xff of a part of ISO 8859 past the first
+    fn iso8859_high(self) -> Option<&'static [char; 96]> {
+        let high = match self {
+            Charset::Iso8859_2 => &ISO8859_2_HIGH,
+            Charset::Iso8859_3 => &ISO8859_3_HIGH,
+            Charset::Iso8859_4 => &ISO8859_4_HIGH,
+            Charset::Iso8859_5 => &ISO8859_5_HIGH,
+            Charset::Iso8859_6 => &ISO8859_6_HIGH,
+            Charset::Iso8859_7 => &ISO8859_7_HIGH,
+            Charset::Iso8859_8 => &ISO8859_8_HIGH,
+            Charset::Iso8859_9 => &ISO8859_9_HIGH,
+            Charset::Iso8859_10 => &ISO8859_10_HIGH,
+            Charset::Iso8859_11 => &ISO8859_11_HIGH,
+            Charset::Iso8859_13 => &ISO8859_13_HIGH,
+            Charset::Iso8859_14 => &ISO8859_14_HIGH,
+            Charset::Iso8859_15 => &ISO8859_15_HIGH,
+            Charset::Iso8859_16 => &ISO8859_16_HIGH,
+            _ => return None,
+        };
+        Some(high)
+    }
+}
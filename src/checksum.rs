@@ -0,0 +1,253 @@
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                1 => 0xedb8_8320 ^ (crc >> 1),
+                _ => crc >> 1,
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// Running CRC-32 (the IEEE polynomial used by gzip, zip and PNG)
+pub struct Crc32(u32);
+
+impl Crc32 {
+    pub fn new() -> Self {
+        Crc32(0xffff_ffff)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.0 = CRC32_TABLE[((self.0 ^ byte as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+const fn crc64_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                1 => 0xc96c_5795_d787_0f42 ^ (crc >> 1),
+                _ => crc >> 1,
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+}
+
+const CRC64_TABLE: [u64; 256] = crc64_table();
+
+/// Running CRC-64 (the ECMA-182 polynomial used by xz)
+pub struct Crc64(u64);
+
+impl Crc64 {
+    pub fn new() -> Self {
+        Crc64(u64::MAX)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.0 = CRC64_TABLE[((self.0 ^ byte as u64) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        !self.0
+    }
+}
+
+/// Running Adler-32 (the checksum of zlib streams)
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    const MODULUS: u32 = 65521;
+
+    pub fn new() -> Self {
+        Adler32 { a: 1, b: 0 }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.a = (self.a + byte as u32) % Self::MODULUS;
+            self.b = (self.b + self.a) % Self::MODULUS;
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        self.b << 16 | self.a
+    }
+}
+
+const XXH_PRIME1: u64 = 0x9e37_79b1_85eb_ca87;
+const XXH_PRIME2: u64 = 0xc2b2_ae3d_27d4_eb4f;
+const XXH_PRIME3: u64 = 0x1656_67b1_9e37_79f9;
+const XXH_PRIME4: u64 = 0x85eb_ca77_c2b2_ae63;
+const XXH_PRIME5: u64 = 0x27d4_eb2f_1656_67c5;
+
+fn xxh64_round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(XXH_PRIME2))
+        .rotate_left(31)
+        .wrapping_mul(XXH_PRIME1)
+}
+
+/// Running XXH64 with a seed of 0 (the content checksum of zstd frames)
+pub struct Xxh64 {
+    acc: [u64; 4],
+    /// Bytes not yet making up a whole stripe of 32
+    pending: Vec<u8>,
+    total: u64,
+}
+
+impl Xxh64 {
+    pub fn new() -> Self {
+        Xxh64 {
+            acc: [
+                XXH_PRIME1.wrapping_add(XXH_PRIME2),
+                XXH_PRIME2,
+                0,
+                XXH_PRIME1.wrapping_neg(),
+            ],
+            pending: Vec::with_capacity(32),
+            total: 0,
+        }
+    }
+
+    fn stripe(&mut self, stripe: &[u8]) {
+        for (acc, lane) in self.acc.iter_mut().zip(stripe.chunks_exact(8)) {
+            *acc = xxh64_round(*acc, u64::from_le_bytes(lane.try_into().unwrap()));
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total += data.len() as u64;
+        if !self.pending.is_empty() {
+            let taken = data.len().min(32 - self.pending.len());
+            self.pending.extend_from_slice(&data[..taken]);
+            data = &data[taken..];
+            if self.pending.len() < 32 {
+                return;
+            }
+            let stripe = std::mem::take(&mut self.pending);
+            self.stripe(&stripe);
+        }
+        let mut stripes = data.chunks_exact(32);
+        for stripe in &mut stripes {
+            self.stripe(stripe);
+        }
+        self.pending.extend_from_slice(stripes.remainder());
+    }
+
+    pub fn finish(&self) -> u64 {
+        let mut hash = match self.total >= 32 {
+            true => {
+                let [v1, v2, v3, v4] = self.acc;
+                let hash = v1
+                    .rotate_left(1)
+                    .wrapping_add(v2.rotate_left(7))
+                    .wrapping_add(v3.rotate_left(12))
+                    .wrapping_add(v4.rotate_left(18));
+                self.acc.iter().fold(hash, |hash, &acc| {
+                    (hash ^ xxh64_round(0, acc))
+                        .wrapping_mul(XXH_PRIME1)
+                        .wrapping_add(XXH_PRIME4)
+                })
+            }
+            false => XXH_PRIME5,
+        };
+        hash = hash.wrapping_add(self.total);
+
+        let mut rest = self.pending.as_slice();
+        while rest.len() >= 8 {
+            let lane = u64::from_le_bytes(rest[..8].try_into().unwrap());
+            hash ^= xxh64_round(0, lane);
+            hash = hash
+                .rotate_left(27)
+                .wrapping_mul(XXH_PRIME1)
+                .wrapping_add(XXH_PRIME4);
+            rest = &rest[8..];
+        }
+        if rest.len() >= 4 {
+            let lane = u32::from_le_bytes(rest[..4].try_into().unwrap()) as u64;
+            hash ^= lane.wrapping_mul(XXH_PRIME1);
+            hash = hash
+                .rotate_left(23)
+                .wrapping_mul(XXH_PRIME2)
+                .wrapping_add(XXH_PRIME3);
+            rest = &rest[4..];
+        }
+        for &byte in rest {
+            hash ^= (byte as u64).wrapping_mul(XXH_PRIME5);
+            hash = hash.rotate_left(11).wrapping_mul(XXH_PRIME1);
+        }
+
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(XXH_PRIME2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(XXH_PRIME3);
+        hash ^ (hash >> 32)
+    }
+}
+
+/// Checksum of --line-checksum, small enough to check a line of a dump by hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LineChecksum {
+    /// Sum of the bytes, modulo 256
+    Sum,
+    /// CRC-8 with the polynomial 0x07 and no reflection, as in SMBus
+    Crc8,
+    /// CRC-16 with the polynomial 0x1021 and an initial value of 0, as in XMODEM
+    Crc16,
+}
+
+impl LineChecksum {
+    pub fn of(self, data: &[u8]) -> u32 {
+        match self {
+            LineChecksum::Sum => data.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) as u32,
+            LineChecksum::Crc8 => data.iter().fold(0u8, |crc, &byte| {
+                (0..8).fold(crc ^ byte, |crc, _| match crc & 0x80 {
+                    0 => crc << 1,
+                    _ => (crc << 1) ^ 0x07,
+                })
+            }) as u32,
+            LineChecksum::Crc16 => data.iter().fold(0u16, |crc, &byte| {
+                (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| match crc & 0x8000 {
+                    0 => crc << 1,
+                    _ => (crc << 1) ^ 0x1021,
+                })
+            }) as u32,
+        }
+    }
+
+    /// Number of hex digits of the checksum
+    pub fn digits(self) -> usize {
+        match self {
+            LineChecksum::Sum | LineChecksum::Crc8 => 2,
+            LineChecksum::Crc16 => 4,
+        }
+    }
+}
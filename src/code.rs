@@ -0,0 +1,212 @@
+use std::io::{self, Write};
+
+use crate::render::Renderer;
+
+/// Language of the source code written by the code styles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Lang {
+    C,
+    Rust,
+    Python,
+    Go,
+    Java,
+    Csharp,
+}
+
+/// Visibility of the constant, for the languages that have one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Visibility {
+    Pub,
+    /// Visible within the crate, package or assembly
+    Crate,
+    Private,
+}
+
+impl Visibility {
+    fn prefix(self, lang: Lang) -> &'static str {
+        match (lang, self) {
+            (Lang::Rust, Visibility::Pub) => "pub ",
+            (Lang::Rust, Visibility::Crate) => "pub(crate) ",
+            (Lang::Java | Lang::Csharp, Visibility::Pub) => "public ",
+            // Java's package private has no keyword
+            (Lang::Java, Visibility::Crate) => "",
+            (Lang::Csharp, Visibility::Crate) => "internal ",
+            (Lang::Java | Lang::Csharp, Visibility::Private) => "private ",
+            _ => "",
+        }
+    }
+}
+
+/// Turns a file name into an identifier like xxd does, replacing anything but
+/// letters and digits with underscores
+pub fn identifier(name: &str) -> String {
+    let identifier: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    match identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("__{identifier}"),
+        false => identifier,
+    }
+}
+
+/// A `--name`, which has to be an identifier already, as it goes into the code as is
+pub fn parse_name(name: &str) -> Result<String, String> {
+    match !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        true => Ok(name.to_string()),
+        false => Err(format!(
+            "Invalid name \"{name}\", expected letters, digits and underscores, not starting with a digit"
+        )),
+    }
+}
+
+/// Identifier for a constant holding the contents of the file called `name`,
+/// in the case conventional for `lang`
+pub fn constant_name(name: &str, lang: Lang) -> String {
+    match lang {
+        Lang::Rust | Lang::Python | Lang::Java => identifier(name).to_uppercase(),
+        Lang::C | Lang::Go | Lang::Csharp => identifier(name),
+    }
+}
+
+/// Renders the bytes as an array or literal in source code, like `xxd -i`
+pub struct Code {
+    lang: Lang,
+    /// Name of the constant, or None to only write its elements
+    name: Option<String>,
+    uppercase: bool,
+    visibility: Visibility,
+    /// Whether Rust gets a `&[u8]` slice rather than an array of known length
+    slice: bool,
+    len: usize,
+    /// Elements held back until the length that precedes them is known
+    pending: Option<String>,
+}
+
+impl Code {
+    pub fn new(
+        lang: Lang,
+        name: Option<String>,
+        uppercase: bool,
+        visibility: Visibility,
+        slice: bool,
+    ) -> Self {
+        let needs_len = lang == Lang::Rust && !slice && name.is_some();
+        Code {
+            lang,
+            name,
+            uppercase,
+            visibility,
+            slice,
+            len: 0,
+            pending: needs_len.then(String::new),
+        }
+    }
+
+    fn element(&self, byte: u8) -> String {
+        let hex = match self.uppercase {
+            true => format!("{byte:02X}"),
+            false => format!("{byte:02x}"),
+        };
+        match self.lang {
+            Lang::C if self.uppercase => format!("0X{hex}"),
+            Lang::Python => format!("\\x{hex}"),
+            // Java's bytes are signed
+            Lang::Java if byte > 0x7f => format!("(byte) 0x{hex}"),
+            _ => format!("0x{hex}"),
+        }
+    }
+
+    /// The declaration that opens the constant, written once its length is known if it's needed
+    fn declaration(&self, name: &str) -> String {
+        let visibility = self.visibility.prefix(self.lang);
+        match (self.lang, self.slice) {
+            (Lang::C, _) => format!("unsigned char {name}[] = {{\n"),
+            (Lang::Rust, true) => format!("{visibility}const {name}: &[u8] = &[\n"),
+            (Lang::Rust, false) => format!("{visibility}const {name}: [u8; {}] = [\n", self.len),
+            (Lang::Python, _) => format!("{name} = (\n"),
+            (Lang::Go, _) => format!("var {name} = []byte{{\n"),
+            (Lang::Java, _) => format!("{visibility}static final byte[] {name} = {{\n"),
+            (Lang::Csharp, _) => format!("{visibility}static readonly byte[] {name} = {{\n"),
+        }
+    }
+
+    /// Whatever closes the constant after its last element
+    fn closing(&self, name: &str) -> String {
+        match self.lang {
+            Lang::C => format!("}};\nunsigned int {name}_len = {};\n", self.len),
+            Lang::Rust => "];\n".to_string(),
+            // An empty pair of parentheses would be a tuple rather than bytes
+            Lang::Python if self.len == 0 => "    b\"\"\n)\n".to_string(),
+            Lang::Python => ")\n".to_string(),
+            Lang::Go => "}\n".to_string(),
+            Lang::Java | Lang::Csharp => "};\n".to_string(),
+        }
+    }
+}
+
+impl Renderer for Code {
+    fn begin(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        match (&self.name, &self.pending) {
+            (Some(name), None) => writer.write_all(self.declaration(name).as_bytes()),
+            _ => Ok(()),
+        }
+    }
+
+    fn line(&mut self, writer: &mut dyn Write, _addr: usize, bytes: &[u8]) -> io::Result<()> {
+        let elements: Vec<_> = bytes.iter().map(|&byte| self.element(byte)).collect();
+        let line = match self.lang {
+            // C has no trailing commas, so the separator is only written once it's known
+            // that more elements follow
+            Lang::C if self.len > 0 => format!(",\n  {}", elements.join(", ")),
+            Lang::C => format!("  {}", elements.join(", ")),
+            // Adjacent bytes literals are concatenated
+            Lang::Python => format!("    b\"{}\"\n", elements.concat()),
+            Lang::Go => format!("\t{},\n", elements.join(", ")),
+            Lang::Rust | Lang::Java | Lang::Csharp => format!("    {},\n", elements.join(", ")),
+        };
+        self.len += bytes.len();
+
+        match &mut self.pending {
+            Some(pending) => {
+                pending.push_str(&line);
+                Ok(())
+            }
+            None => writer.write_all(line.as_bytes()),
+        }
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        if let (Some(name), Some(pending)) = (&self.name, &self.pending) {
+            writer.write_all(self.declaration(name).as_bytes())?;
+            writer.write_all(pending.as_bytes())?;
+        }
+        if self.lang == Lang::C && self.len > 0 {
+            writeln!(writer)?;
+        }
+        match &self.name {
+            Some(name) => writer.write_all(self.closing(name).as_bytes()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_identifiers() {
+        assert_eq!(parse_name("_data2").unwrap(), "_data2");
+        for name in ["", "2data", "a b", "x[]; y", "x\"", "a-b"] {
+            assert!(parse_name(name).is_err(), "{name:?} was accepted");
+        }
+        assert_eq!(identifier("2 a-b.bin"), "__2_a_b_bin");
+    }
+}
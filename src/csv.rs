@@ -0,0 +1,54 @@
+use std::io::{self, Write};
+
+use crate::render::{printable_char, Renderer};
+
+/// Renders a table with a row for every byte, separated by commas or tabs
+pub struct Csv {
+    separator: char,
+    uppercase: bool,
+    /// Number of bytes written so far
+    index: usize,
+}
+
+impl Csv {
+    pub fn new(separator: char, uppercase: bool) -> Self {
+        Csv {
+            separator,
+            uppercase,
+            index: 0,
+        }
+    }
+
+    /// Quotes a field containing the separator or a quote
+    fn field(&self, text: &str) -> String {
+        match text.contains(self.separator) || text.contains('"') {
+            true => format!("\"{}\"", text.replace('"', "\"\"")),
+            false => text.to_string(),
+        }
+    }
+}
+
+impl Renderer for Csv {
+    fn begin(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        let columns = ["offset", "index", "hex", "decimal", "ascii"];
+        writeln!(writer, "{}", columns.join(&self.separator.to_string()))
+    }
+
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        let separator = self.separator;
+        for (offset, &byte) in (addr..).zip(bytes) {
+            let hex = match self.uppercase {
+                true => format!("{byte:02X}"),
+                false => format!("{byte:02x}"),
+            };
+            let ascii = self.field(&printable_char(byte).to_string());
+            writeln!(
+                writer,
+                "{offset}{separator}{}{separator}{hex}{separator}{byte}{separator}{ascii}",
+                self.index
+            )?;
+            self.index += 1;
+        }
+        Ok(())
+    }
+}
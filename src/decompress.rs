@@ -0,0 +1,556 @@
+use std::io::{self, BufRead, Read};
+
+use crate::checksum::{Crc32, Crc64};
+use crate::inflate::Inflate;
+use crate::lzma::Lzma2;
+use crate::zstd::ZstdDecoder;
+
+/// The magic bytes of gzip, followed by the only compression method it has
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b, 0x08];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Number of leading bytes needed to recognize every supported format
+pub const MAGIC_LEN: usize = 6;
+
+const FLAG_HCRC: u8 = 0x02;
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+const FLAG_COMMENT: u8 = 0x10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    /// Recognizes a compressed stream by its first bytes
+    pub fn detect(magic: &[u8]) -> Option<Self> {
+        if magic.starts_with(GZIP_MAGIC) {
+            Some(Compression::Gzip)
+        } else if magic.starts_with(XZ_MAGIC) {
+            Some(Compression::Xz)
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Wraps `reader` in a decoder for this format
+    pub fn decoder<R: BufRead + 'static>(self, reader: R) -> io::Result<Box<dyn Read>> {
+        match self {
+            Compression::Gzip => Ok(Box::new(GzipDecoder::new(reader)?)),
+            Compression::Xz => Ok(Box::new(XzDecoder::new(reader)?)),
+            Compression::Zstd => Ok(Box::new(ZstdDecoder::new(reader)?)),
+        }
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid gzip data: {message}"),
+    )
+}
+
+fn invalid_xz(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid xz data: {message}"),
+    )
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut bytes = [0u8; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Skips past a gzip member header (RFC 1952), leaving `reader` at the compressed data
+fn read_gzip_header(reader: &mut impl BufRead) -> io::Result<()> {
+    let mut header = [0u8; 10];
+    reader.read_exact(&mut header)?;
+    if !header.starts_with(GZIP_MAGIC) {
+        return Err(invalid("bad magic bytes or unknown compression method"));
+    }
+
+    let flags = header[3];
+    if flags & FLAG_EXTRA != 0 {
+        let len = read_u16(reader)?;
+        io::copy(&mut reader.take(len as u64), &mut io::sink())?;
+    }
+    for flag in [FLAG_NAME, FLAG_COMMENT] {
+        if flags & flag != 0 {
+            // Zero terminated file name or comment
+            while read_u8(reader)? != 0 {}
+        }
+    }
+    if flags & FLAG_HCRC != 0 {
+        read_u16(reader)?;
+    }
+
+    Ok(())
+}
+
+/// Decoder for (possibly multi-member) gzip streams
+pub struct GzipDecoder<R> {
+    member: Option<Inflate<R>>,
+    crc: Crc32,
+    size: u32,
+}
+
+impl<R: BufRead> GzipDecoder<R> {
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        read_gzip_header(&mut reader)?;
+        Ok(GzipDecoder {
+            member: Some(Inflate::new(reader)),
+            crc: Crc32::new(),
+            size: 0,
+        })
+    }
+
+    /// Checks the trailer of the finished member and starts the next one, if any
+    fn next_member(&mut self, mut reader: R) -> io::Result<()> {
+        if read_u32(&mut reader)? != self.crc.finish() {
+            return Err(invalid("CRC mismatch"));
+        }
+        if read_u32(&mut reader)? != self.size {
+            return Err(invalid("size mismatch"));
+        }
+
+        if !reader.fill_buf()?.is_empty() {
+            read_gzip_header(&mut reader)?;
+            self.member = Some(Inflate::new(reader));
+            self.crc = Crc32::new();
+            self.size = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for GzipDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while let Some(member) = &mut self.member {
+            let read = member.read(buf)?;
+            if read > 0 {
+                self.crc.update(&buf[..read]);
+                self.size = self.size.wrapping_add(read as u32);
+                return Ok(read);
+            }
+
+            let reader = self.member.take().expect("member is present").into_inner();
+            self.next_member(reader)?;
+        }
+
+        Ok(0)
+    }
+}
+
+const XZ_FOOTER_MAGIC: &[u8] = b"YZ";
+const XZ_FILTER_LZMA2: u64 = 0x21;
+const XZ_CHECK_CRC32: u8 = 0x01;
+const XZ_CHECK_CRC64: u8 = 0x04;
+
+/// Variable length integer of the xz format, seven bits to a byte
+fn read_varint(reader: &mut impl Read) -> io::Result<u64> {
+    let mut value = 0;
+    for index in 0..9 {
+        let byte = read_u8(reader)?;
+        value |= ((byte & 0x7f) as u64) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_xz("integer too long"))
+}
+
+/// Skips the zeros padding `len` bytes up to a multiple of four
+fn skip_xz_padding(reader: &mut impl Read, len: u64) -> io::Result<()> {
+    let mut padding = [0u8; 3];
+    let padding = &mut padding[..((4 - len % 4) % 4) as usize];
+    reader.read_exact(padding)?;
+    match padding.iter().all(|&byte| byte == 0) {
+        true => Ok(()),
+        false => Err(invalid_xz("bad padding")),
+    }
+}
+
+/// Checks the header of an xz stream, returning the type of check its blocks end with
+fn read_xz_header(reader: &mut impl Read) -> io::Result<u8> {
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header)?;
+    if !header.starts_with(XZ_MAGIC) {
+        return Err(invalid_xz("bad magic bytes"));
+    }
+    let mut crc = Crc32::new();
+    crc.update(&header[6..8]);
+    if u32::from_le_bytes(header[8..12].try_into().unwrap()) != crc.finish() {
+        return Err(invalid_xz("header CRC mismatch"));
+    }
+    match header[6] == 0 && header[7] <= 0x0f {
+        true => Ok(header[7]),
+        false => Err(invalid_xz("unknown stream flags")),
+    }
+}
+
+/// Check of the uncompressed data of an xz block
+enum XzCheck {
+    Crc32(Crc32),
+    Crc64(Crc64),
+    /// A check that isn't verified, only skipped over, like SHA-256
+    Unverified(usize),
+}
+
+impl XzCheck {
+    fn new(check: u8) -> Self {
+        match check {
+            XZ_CHECK_CRC32 => XzCheck::Crc32(Crc32::new()),
+            XZ_CHECK_CRC64 => XzCheck::Crc64(Crc64::new()),
+            0 => XzCheck::Unverified(0),
+            _ => XzCheck::Unverified(4 << ((check - 1) / 3)),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            XzCheck::Crc32(crc) => crc.update(data),
+            XzCheck::Crc64(crc) => crc.update(data),
+            XzCheck::Unverified(_) => {}
+        }
+    }
+
+    /// Number of bytes the check takes after the block
+    fn len(&self) -> u64 {
+        match self {
+            XzCheck::Crc32(_) => 4,
+            XzCheck::Crc64(_) => 8,
+            &XzCheck::Unverified(len) => len as u64,
+        }
+    }
+
+    fn verify(&self, reader: &mut impl Read) -> io::Result<()> {
+        let matches = match self {
+            XzCheck::Crc32(crc) => read_u32(reader)? == crc.finish(),
+            XzCheck::Crc64(crc) => {
+                let mut bytes = [0u8; 8];
+                reader.read_exact(&mut bytes)?;
+                u64::from_le_bytes(bytes) == crc.finish()
+            }
+            &XzCheck::Unverified(len) => {
+                io::copy(&mut reader.take(len as u64), &mut io::sink())?;
+                true
+            }
+        };
+        match matches {
+            true => Ok(()),
+            false => Err(invalid_xz("check mismatch")),
+        }
+    }
+}
+
+struct XzBlock {
+    lzma: Lzma2,
+    check: XzCheck,
+    /// Bytes of the block read so far, its header included
+    len: u64,
+    /// Bytes decoded from the block so far
+    decoded: u64,
+}
+
+/// Reader that keeps the bytes read through it, to check them once they all are
+struct Recording<R> {
+    inner: R,
+    bytes: Vec<u8>,
+}
+
+impl<R: Read> Read for Recording<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+}
+
+/// Decoder for (possibly concatenated) xz streams whose blocks use the LZMA2 filter alone
+pub struct XzDecoder<R> {
+    reader: R,
+    /// Type of check of the current stream
+    check: u8,
+    block: Option<XzBlock>,
+    /// Size of every block of the current stream before its padding, and of its data, which
+    /// the index has to list
+    blocks: Vec<(u64, u64)>,
+    decoded: Vec<u8>,
+    pos: usize,
+    finished: bool,
+}
+
+impl<R: BufRead> XzDecoder<R> {
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let check = read_xz_header(&mut reader)?;
+        Ok(XzDecoder {
+            reader,
+            check,
+            block: None,
+            blocks: Vec::new(),
+            decoded: Vec::new(),
+            pos: 0,
+            finished: false,
+        })
+    }
+
+    /// Reads a block header, or the index and footer ending the stream when no block is left
+    fn next_block(&mut self) -> io::Result<()> {
+        let size = read_u8(&mut self.reader)?;
+        if size == 0 {
+            return self.next_stream();
+        }
+
+        let mut header = vec![0u8; (size as usize + 1) * 4];
+        header[0] = size;
+        self.reader.read_exact(&mut header[1..])?;
+        let (fields, crc) = header.split_at(header.len() - 4);
+        let mut expected = Crc32::new();
+        expected.update(fields);
+        if u32::from_le_bytes(crc.try_into().unwrap()) != expected.finish() {
+            return Err(invalid_xz("block header CRC mismatch"));
+        }
+
+        let flags = fields[1];
+        if flags & 0x3c != 0 {
+            return Err(invalid_xz("unknown block flags"));
+        }
+        let mut fields = &fields[2..];
+        // The compressed and uncompressed sizes, when given, are only hints
+        for flag in [0x40, 0x80] {
+            if flags & flag != 0 {
+                read_varint(&mut fields)?;
+            }
+        }
+        let filters = (flags & 0x03) + 1;
+        let id = read_varint(&mut fields)?;
+        let properties_len = read_varint(&mut fields)?;
+        if filters > 1 || id != XZ_FILTER_LZMA2 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "xz filters other than LZMA2 alone aren't supported",
+            ));
+        }
+        if properties_len != 1 {
+            return Err(invalid_xz("bad LZMA2 properties"));
+        }
+        let dict_size = Lzma2::dict_size(read_u8(&mut fields)?)?;
+
+        self.block = Some(XzBlock {
+            lzma: Lzma2::new(dict_size),
+            check: XzCheck::new(self.check),
+            len: header.len() as u64,
+            decoded: 0,
+        });
+        Ok(())
+    }
+
+    /// Checks the index and footer of the stream against its blocks, then starts the next
+    /// stream, if any
+    fn next_stream(&mut self) -> io::Result<()> {
+        // The index indicator was read before
+        let mut index = Recording {
+            inner: &mut self.reader,
+            bytes: vec![0],
+        };
+        if read_varint(&mut index)? != self.blocks.len() as u64 {
+            return Err(invalid_xz("index doesn't match the blocks"));
+        }
+        for &(unpadded, decoded) in &self.blocks {
+            if read_varint(&mut index)? != unpadded || read_varint(&mut index)? != decoded {
+                return Err(invalid_xz("index doesn't match the blocks"));
+            }
+        }
+        let len = index.bytes.len() as u64;
+        skip_xz_padding(&mut index, len)?;
+        let mut crc = Crc32::new();
+        crc.update(&index.bytes);
+        let index_len = index.bytes.len() + 4;
+        if read_u32(&mut self.reader)? != crc.finish() {
+            return Err(invalid_xz("index CRC mismatch"));
+        }
+
+        let mut footer = [0u8; 12];
+        self.reader.read_exact(&mut footer)?;
+        let mut crc = Crc32::new();
+        crc.update(&footer[4..10]);
+        let backward_size = u32::from_le_bytes(footer[4..8].try_into().unwrap()) as usize;
+        if !footer.ends_with(XZ_FOOTER_MAGIC)
+            || u32::from_le_bytes(footer[..4].try_into().unwrap()) != crc.finish()
+            || (backward_size + 1) * 4 != index_len
+            || footer[8..10] != [0, self.check]
+        {
+            return Err(invalid_xz("bad footer"));
+        }
+        self.blocks.clear();
+
+        // Streams may follow, with zeros between them in multiples of four
+        loop {
+            match self.reader.fill_buf()?.first() {
+                None => {
+                    self.finished = true;
+                    return Ok(());
+                }
+                Some(0) => {
+                    if read_u32(&mut self.reader)? != 0 {
+                        return Err(invalid_xz("bad stream padding"));
+                    }
+                }
+                Some(_) => {
+                    self.check = read_xz_header(&mut self.reader)?;
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Read for XzDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.decoded.len() && !self.finished {
+            self.decoded.clear();
+            self.pos = 0;
+            match &mut self.block {
+                Some(block) if block.lzma.is_finished() => {
+                    skip_xz_padding(&mut self.reader, block.len)?;
+                    block.check.verify(&mut self.reader)?;
+                    self.blocks
+                        .push((block.len + block.check.len(), block.decoded));
+                    self.block = None;
+                }
+                Some(block) => {
+                    let mut compressed = (&mut self.reader).take(u64::MAX);
+                    block.lzma.chunk(&mut compressed, &mut self.decoded)?;
+                    block.len += u64::MAX - compressed.limit();
+                    block.decoded += self.decoded.len() as u64;
+                    block.check.update(&self.decoded);
+                }
+                None => self.next_block()?,
+            }
+        }
+
+        let len = buf.len().min(self.decoded.len() - self.pos);
+        buf[..len].copy_from_slice(&self.decoded[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, read_all};
+
+    const SAMPLE_GZ: &[u8] = include_bytes!("../tests/fixtures/sample.gz");
+    const MEMBERS_GZ: &[u8] = include_bytes!("../tests/fixtures/members.gz");
+    const BLOCKS_XZ: &[u8] = include_bytes!("../tests/fixtures/blocks.xz");
+    const CRC32_XZ: &[u8] = include_bytes!("../tests/fixtures/crc32.xz");
+    const SHA256_XZ: &[u8] = include_bytes!("../tests/fixtures/sha256.xz");
+    const STREAMS_XZ: &[u8] = include_bytes!("../tests/fixtures/streams.xz");
+    const DELTA_XZ: &[u8] = include_bytes!("../tests/fixtures/delta.xz");
+    const X86_XZ: &[u8] = include_bytes!("../tests/fixtures/x86.xz");
+
+    fn decode_as(compression: Compression, data: &[u8]) -> io::Result<Vec<u8>> {
+        read_all(compression.decoder(io::Cursor::new(data.to_vec()))?)
+    }
+
+    fn decode(data: &[u8]) -> io::Result<Vec<u8>> {
+        decode_as(
+            Compression::detect(data).expect("a compressed fixture"),
+            data,
+        )
+    }
+
+    #[test]
+    fn detects_every_format() {
+        assert_eq!(Compression::detect(SAMPLE_GZ), Some(Compression::Gzip));
+        assert_eq!(Compression::detect(BLOCKS_XZ), Some(Compression::Xz));
+        let zstd = include_bytes!("../tests/fixtures/sample.zst");
+        assert_eq!(Compression::detect(zstd), Some(Compression::Zstd));
+        assert_eq!(Compression::detect(b"\x1f\x8b\x07"), None);
+    }
+
+    #[test]
+    fn decodes_gzip_members_one_after_another() {
+        assert_eq!(decode(SAMPLE_GZ).unwrap(), fixtures::sample());
+        let expected = [fixtures::SHORT, &fixtures::sample()].concat();
+        assert_eq!(decode(MEMBERS_GZ).unwrap(), expected);
+    }
+
+    #[test]
+    fn decodes_xz_blocks_with_every_check() {
+        assert_eq!(decode(BLOCKS_XZ).unwrap(), fixtures::sample());
+        assert_eq!(decode(CRC32_XZ).unwrap(), fixtures::sample());
+        assert_eq!(decode(SHA256_XZ).unwrap(), fixtures::SHORT);
+    }
+
+    #[test]
+    fn decodes_xz_streams_one_after_another() {
+        let expected = [fixtures::SHORT, &fixtures::sample()].concat();
+        assert_eq!(decode(STREAMS_XZ).unwrap(), expected);
+        // Streams may be padded apart with zeros
+        let padded = [SHA256_XZ, &[0; 8], STREAMS_XZ].concat();
+        let expected = [fixtures::SHORT, &expected].concat();
+        assert_eq!(decode(&padded).unwrap(), expected);
+    }
+
+    #[test]
+    fn xz_filters_other_than_lzma2_are_rejected() {
+        for data in [DELTA_XZ, X86_XZ] {
+            let error = decode(data).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        }
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        for (compression, fixture) in [
+            (Compression::Gzip, SAMPLE_GZ),
+            (Compression::Gzip, MEMBERS_GZ),
+            (Compression::Xz, BLOCKS_XZ),
+            (Compression::Xz, STREAMS_XZ),
+        ] {
+            for data in fixtures::truncations(fixture, 127) {
+                let result = decode_as(compression, data);
+                assert!(result.is_err(), "{} bytes", data.len());
+            }
+        }
+    }
+
+    #[test]
+    fn corrupt_data_is_an_error() {
+        for (compression, fixture) in [
+            (Compression::Gzip, SAMPLE_GZ),
+            (Compression::Xz, BLOCKS_XZ),
+            (Compression::Xz, CRC32_XZ),
+        ] {
+            for data in fixtures::corruptions(fixture, 67) {
+                assert!(decode_as(compression, &data).is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+/// Shannon entropy of `bytes`, in bits per byte from 0 to 8
+pub fn shannon(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &byte in bytes {
+        counts[byte as usize] += 1;
+    }
+    shannon_counts(&counts)
+}
+
+/// Shannon entropy of bytes occurring as often as `counts` says for each value
+pub fn shannon_counts(counts: &[usize; 256]) -> f64 {
+    let len = counts.iter().sum::<usize>() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let probability = count as f64 / len;
+            probability * (1.0 / probability).log2()
+        })
+        .sum()
+}
+
+/// Highest entropy `len` bytes can have, when none of them repeat
+pub fn max_shannon(len: usize) -> f64 {
+    (len.clamp(1, 256) as f64).log2()
+}
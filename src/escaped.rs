@@ -0,0 +1,185 @@
+use std::io::{self, Write};
+
+use crate::render::Renderer;
+
+fn invalid(offset: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid escaped input at offset {offset}: {message}"),
+    )
+}
+
+/// Parses up to `max_len` digits of `radix` starting at `start`, returning the value and its end
+fn parse_digits(text: &[u8], start: usize, radix: u32, max_len: usize) -> Option<(u32, usize)> {
+    let len = text[start..]
+        .iter()
+        .take(max_len)
+        .take_while(|byte| (**byte as char).is_digit(radix))
+        .count();
+    let digits = std::str::from_utf8(&text[start..start + len]).ok()?;
+    u32::from_str_radix(digits, radix)
+        .ok()
+        .map(|value| (value, start + len))
+}
+
+/// Decodes the backslash escape starting at `start`, returning the byte and the escape's end
+fn parse_escape(text: &[u8], start: usize) -> io::Result<(u8, usize)> {
+    let escape = *text
+        .get(start + 1)
+        .ok_or_else(|| invalid(start, "trailing backslash"))?;
+    let simple = |byte: u8| Ok((byte, start + 2));
+    match escape {
+        b'x' => match parse_digits(text, start + 2, 16, 2) {
+            Some((value, end)) => Ok((value as u8, end)),
+            None => Err(invalid(start, "\\x without hex digits")),
+        },
+        b'0'..=b'7' => {
+            let (value, end) = parse_digits(text, start + 1, 8, 3).expect("starts with a digit");
+            let value =
+                u8::try_from(value).map_err(|_| invalid(start, "octal escape above 0o377"))?;
+            Ok((value, end))
+        }
+        b'n' => simple(b'\n'),
+        b'r' => simple(b'\r'),
+        b't' => simple(b'\t'),
+        b'a' => simple(0x07),
+        b'b' => simple(0x08),
+        b'e' => simple(0x1b),
+        b'f' => simple(0x0c),
+        b'v' => simple(0x0b),
+        b'\\' | b'\'' | b'"' | b'?' => simple(escape),
+        _ => Err(invalid(
+            start,
+            &format!("unknown escape \\{}", escape as char),
+        )),
+    }
+}
+
+/// Decodes bytes written as `\x7f\x45` escapes, `0x7f, 0x45` lists or C and Python
+/// string literals, ignoring the surrounding declarations, punctuation and comments
+pub fn decode(text: &[u8]) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    // The quote character of the string literal being read, if inside one
+    let mut quote: Option<u8> = None;
+    // Where the last escape ended, to tell the text run into it from that of declarations
+    let mut escape_end = None;
+    let mut index = 0;
+
+    while index < text.len() {
+        let byte = text[index];
+        match (quote, byte) {
+            (_, b'\\') => {
+                let (value, end) = parse_escape(text, index)?;
+                bytes.push(value);
+                index = end;
+                escape_end = Some(end);
+            }
+            (Some(open), _) if byte == open => {
+                quote = None;
+                index += 1;
+            }
+            (Some(_), _) => {
+                bytes.push(byte);
+                index += 1;
+            }
+            (None, b'"' | b'\'') => {
+                quote = Some(byte);
+                index += 1;
+            }
+            (None, b'0') if matches!(text.get(index + 1), Some(b'x' | b'X')) => {
+                let (value, end) = parse_digits(text, index + 2, 16, 8)
+                    .ok_or_else(|| invalid(index, "0x without hex digits"))?;
+                let value = u8::try_from(value).map_err(|_| invalid(index, "value above 0xff"))?;
+                bytes.push(value);
+                index = end;
+            }
+            (None, b'/') if text.get(index + 1) == Some(&b'/') => {
+                index = text[index..]
+                    .iter()
+                    .position(|&byte| byte == b'\n')
+                    .map_or(text.len(), |len| index + len);
+            }
+            (None, b'/') if text.get(index + 1) == Some(&b'*') => {
+                index = text[index + 2..]
+                    .windows(2)
+                    .position(|pair| pair == b"*/")
+                    .map_or(text.len(), |len| index + 2 + len + 2);
+            }
+            // Identifiers and decimal numbers, like a declaration's name, type or length,
+            // unless they touch an escape, as the `ELF` of `\x7f\x45ELF` is part of the bytes
+            (None, _) if byte.is_ascii_alphanumeric() || byte == b'_' => {
+                let len = text[index..]
+                    .iter()
+                    .take_while(|byte| byte.is_ascii_alphanumeric() || **byte == b'_')
+                    .count();
+                if escape_end == Some(index) || text.get(index + len) == Some(&b'\\') {
+                    bytes.extend_from_slice(&text[index..index + len]);
+                }
+                index += len;
+            }
+            (None, _) => index += 1,
+        }
+    }
+
+    match quote {
+        Some(_) => Err(invalid(text.len(), "unterminated string literal")),
+        None => Ok(bytes),
+    }
+}
+
+/// Renders the bytes as `\x41\x42` escapes, or as `0x41, 0x42` numbers between `separator`s
+pub struct Escaped {
+    separator: Option<String>,
+    uppercase: bool,
+    started: bool,
+}
+
+impl Escaped {
+    pub fn new(separator: Option<String>, uppercase: bool) -> Self {
+        Escaped {
+            separator,
+            uppercase,
+            started: false,
+        }
+    }
+}
+
+impl Renderer for Escaped {
+    fn line(&mut self, writer: &mut dyn Write, _addr: usize, bytes: &[u8]) -> io::Result<()> {
+        let hex = |byte: &u8| match self.uppercase {
+            true => format!("{byte:02X}"),
+            false => format!("{byte:02x}"),
+        };
+        let line = match &self.separator {
+            Some(separator) => {
+                // Numbers are separated across lines too, but the line doesn't end in spaces
+                if self.started {
+                    writeln!(writer, "{}", separator.trim_end())?;
+                }
+                let numbers: Vec<_> = bytes
+                    .iter()
+                    .map(|byte| format!("0x{}", hex(byte)))
+                    .collect();
+                numbers.join(separator)
+            }
+            None => {
+                if self.started {
+                    writeln!(writer)?;
+                }
+                bytes
+                    .iter()
+                    .map(|byte| format!("\\x{}", hex(byte)))
+                    .collect()
+            }
+        };
+        self.started = true;
+        write!(writer, "{line}")
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        match self.started {
+            true => writeln!(writer),
+            false => Ok(()),
+        }
+    }
+}
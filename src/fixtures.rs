@@ -0,0 +1,56 @@
+//! The data in the compressed fixtures of the decoder tests, as `tests/fixtures/generate.py`
+//! makes them
+
+use std::io::{self, Read};
+
+/// What the short fixtures hold
+pub const SHORT: &[u8] = b"hello, world\n";
+
+/// Lines of text past a window of history, then noise and zeros
+pub fn sample() -> Vec<u8> {
+    let mut data: Vec<u8> = (0..3000)
+        .flat_map(|index| {
+            format!("{index}: the quick brown fox jumps over the lazy dog\n").into_bytes()
+        })
+        .collect();
+    data.extend(noise(4096));
+    data.extend([0; 4096]);
+    data
+}
+
+/// Bytes of a 64-bit linear congruential generator, which don't compress
+pub fn noise(len: usize) -> Vec<u8> {
+    let mut state = 1u64;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 56) as u8
+        })
+        .collect()
+}
+
+/// Everything `reader` reads, or the first error
+pub fn read_all(mut reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Starts of `data` cut off every `step` bytes, and in each of its last few
+pub fn truncations(data: &[u8], step: usize) -> impl Iterator<Item = &[u8]> {
+    let ends = (0..data.len())
+        .step_by(step)
+        .chain(data.len().saturating_sub(4)..data.len());
+    ends.map(|end| &data[..end])
+}
+
+/// Copies of `data` with a byte flipped every `step` bytes
+pub fn corruptions(data: &[u8], step: usize) -> impl Iterator<Item = Vec<u8>> + '_ {
+    (0..data.len()).step_by(step).map(|index| {
+        let mut corrupt = data.to_vec();
+        corrupt[index] ^= 0x55;
+        corrupt
+    })
+}
@@ -0,0 +1,124 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::{self, Write},
+};
+
+use crate::{base64, png::Bitmap};
+
+/// Largest piece of base64 data sent in a single escape of the kitty graphics protocol
+const KITTY_CHUNK_SIZE: usize = 4096;
+/// Rows of pixels in each band of sixels
+const SIXEL_BAND: usize = 6;
+/// Levels of each channel in the color cube images are reduced to for sixel
+const SIXEL_LEVELS: usize = 6;
+
+/// Way of drawing pictures right in the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Protocol {
+    /// The graphics protocol of kitty, also understood by WezTerm and Ghostty
+    Kitty,
+    /// DEC sixel graphics, understood by xterm -ti vt340, foot, mlterm and others
+    Sixel,
+}
+
+impl Protocol {
+    /// The protocol the terminal is known to support, guessed from its environment
+    pub fn detect() -> Option<Self> {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || program == "WezTerm"
+            || program == "ghostty"
+        {
+            return Some(Protocol::Kitty);
+        }
+        let sixel_terms = ["foot", "mlterm", "yaft-256color", "contour"];
+        match sixel_terms.iter().any(|name| term.starts_with(name)) || term.contains("sixel") {
+            true => Some(Protocol::Sixel),
+            false => None,
+        }
+    }
+}
+
+/// Draws `png`, the encoded image, in a terminal with the kitty graphics protocol
+pub fn kitty(writer: &mut impl Write, png: &[u8]) -> io::Result<()> {
+    let data = base64::encode(png);
+    let mut chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).peekable();
+    let mut first = true;
+    while let Some(chunk) = chunks.next() {
+        // Only the first chunk says what to do, the others only whether more follow
+        let more = chunks.peek().is_some() as u8;
+        match first {
+            true => write!(writer, "\x1b_Gf=100,a=T,m={more};")?,
+            false => write!(writer, "\x1b_Gm={more};")?,
+        }
+        writer.write_all(chunk)?;
+        write!(writer, "\x1b\\")?;
+        first = false;
+    }
+    writeln!(writer)
+}
+
+/// Index in the color cube of the color closest to `color`
+fn cube_index(color: [u8; 3]) -> usize {
+    color.iter().fold(0, |index, &channel| {
+        index * SIXEL_LEVELS + (channel as usize * (SIXEL_LEVELS - 1) + 127) / 255
+    })
+}
+
+/// Draws `bitmap` in a terminal with sixel graphics, reducing it to a cube of colors
+pub fn sixel(writer: &mut impl Write, bitmap: &Bitmap) -> io::Result<()> {
+    // Enter sixel mode with square pixels, and give the size up front
+    write!(writer, "\x1bP0;1q\"1;1;{};{}", bitmap.width, bitmap.height)?;
+    for index in 0..SIXEL_LEVELS.pow(3) {
+        let level = |channel: usize| channel * 100 / (SIXEL_LEVELS - 1);
+        let (r, g, b) = (
+            index / (SIXEL_LEVELS * SIXEL_LEVELS),
+            index / SIXEL_LEVELS % SIXEL_LEVELS,
+            index % SIXEL_LEVELS,
+        );
+        write!(writer, "#{index};2;{};{};{}", level(r), level(g), level(b))?;
+    }
+
+    for band in (0..bitmap.height).step_by(SIXEL_BAND) {
+        // The six bits of every column of the band, for each color drawn in it
+        let mut columns: HashMap<usize, Vec<u8>> = HashMap::new();
+        for row in band..(band + SIXEL_BAND).min(bitmap.height) {
+            for x in 0..bitmap.width {
+                let color = cube_index(bitmap.pixels[row * bitmap.width + x]);
+                columns
+                    .entry(color)
+                    .or_insert_with(|| vec![0; bitmap.width])[x] |= 1 << (row - band);
+            }
+        }
+
+        let mut colors: Vec<_> = columns.into_iter().collect();
+        colors.sort_unstable_by_key(|(color, _)| *color);
+        for (color, bits) in colors {
+            write!(writer, "#{color}")?;
+            // Runs of the same sixel are repeated with !count
+            let mut x = 0;
+            while x < bits.len() {
+                let run = bits[x..]
+                    .iter()
+                    .take_while(|&&other| other == bits[x])
+                    .count();
+                let sixel = (bits[x] + 0x3f) as char;
+                match run {
+                    1..=3 => write!(writer, "{}", sixel.to_string().repeat(run))?,
+                    _ => write!(writer, "!{run}{sixel}")?,
+                }
+                x += run;
+            }
+            // Back to the start of the band for the next color
+            write!(writer, "$")?;
+        }
+        write!(writer, "-")?;
+    }
+    writeln!(writer, "\x1b\\")
+}
@@ -0,0 +1,81 @@
+use std::io::{self, Write};
+
+use crate::render::{printable_char, Renderer, Squeeze};
+
+/// Bytes after which an extra space is added, splitting each line in halves
+const HALF_LINE: usize = 8;
+
+/// Renders lines exactly like `hexdump -C`, including its `*` for repeated lines
+pub struct Hexdump {
+    bytes_per_line: usize,
+    uppercase: bool,
+    squeeze: Squeeze,
+}
+
+impl Hexdump {
+    pub fn new(bytes_per_line: usize, uppercase: bool) -> Self {
+        Hexdump {
+            bytes_per_line,
+            uppercase,
+            squeeze: Squeeze::default(),
+        }
+    }
+
+    fn hex_width(&self) -> usize {
+        self.bytes_per_line * 3 + (self.bytes_per_line - 1) / HALF_LINE
+    }
+}
+
+impl Renderer for Hexdump {
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        if self.squeeze.repeats(writer, bytes)? {
+            return Ok(());
+        }
+
+        let mut hex = String::with_capacity(self.hex_width());
+        for (index, byte) in bytes.iter().enumerate() {
+            if index > 0 && index % HALF_LINE == 0 {
+                hex.push(' ');
+            }
+            hex += &match self.uppercase {
+                true => format!("{byte:02X} "),
+                false => format!("{byte:02x} "),
+            };
+        }
+        let ascii: String = bytes.iter().map(|&byte| printable_char(byte)).collect();
+
+        writeln!(
+            writer,
+            "{addr:08x}  {hex:<width$} |{ascii}|",
+            width = self.hex_width()
+        )
+    }
+
+    fn collapses_holes(&self) -> bool {
+        true
+    }
+
+    /// A hole is a run of identical zero lines, so at most two of them are ever shown
+    fn hole(
+        &mut self,
+        writer: &mut dyn Write,
+        addr: usize,
+        len: usize,
+        _is_gap: bool,
+    ) -> io::Result<()> {
+        let zeros = vec![0u8; self.bytes_per_line];
+        self.line(writer, addr, &zeros)?;
+        if len > self.bytes_per_line {
+            self.line(writer, addr + self.bytes_per_line, &zeros)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, end: usize) -> io::Result<()> {
+        // Nothing at all is printed for empty input
+        match self.squeeze.is_empty() {
+            true => Ok(()),
+            false => writeln!(writer, "{end:08x}"),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::io::{self, Write};
+
+use crate::render::{ByteClass, Renderer};
+
+pub const BACKGROUND_COLOR: &str = "#1e1e1e";
+pub const FOREGROUND_COLOR: &str = "#d4d4d4";
+/// Color of addresses and separators
+pub const DIM_COLOR: &str = "#7f7f7f";
+
+/// Color of a byte class, matching that of the terminal
+pub fn class_color(class: ByteClass) -> &'static str {
+    match class {
+        ByteClass::Null => DIM_COLOR,
+        ByteClass::Whitespace => "#00aaaa",
+        ByteClass::Printable => "#55c855",
+        ByteClass::Control => "#ff5555",
+        ByteClass::High => "#d7c000",
+    }
+}
+
+fn stylesheet() -> String {
+    let mut stylesheet = format!(
+        "body {{ background: {BACKGROUND_COLOR}; color: {FOREGROUND_COLOR}; }}\n\
+         pre {{ font-family: ui-monospace, Menlo, Consolas, monospace; }}\n\
+         .dim {{ color: {DIM_COLOR}; }}\n"
+    );
+    for class in ByteClass::ALL {
+        stylesheet += &format!(".{} {{ color: {}; }}\n", class.name(), class_color(class));
+    }
+    stylesheet
+}
+
+/// Escapes the characters that have a meaning in HTML and XML
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn span(class: &str, text: &str) -> String {
+    format!("<span class=\"{class}\">{}</span>", escape(text))
+}
+
+/// Renders a standalone HTML page showing the dump with the colors of the terminal
+pub struct Html {
+    title: String,
+    bytes_per_line: usize,
+    addr_width: usize,
+    uppercase: bool,
+    /// Character shown for each byte in the text column
+    glyph: fn(u8) -> String,
+}
+
+impl Html {
+    pub fn new(
+        title: String,
+        bytes_per_line: usize,
+        addr_width: usize,
+        uppercase: bool,
+        glyph: fn(u8) -> String,
+    ) -> Self {
+        Html {
+            title,
+            bytes_per_line,
+            addr_width,
+            uppercase,
+            glyph,
+        }
+    }
+}
+
+impl Renderer for Html {
+    fn begin(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html>")?;
+        writeln!(writer, "<head>")?;
+        writeln!(writer, "<meta charset=\"utf-8\">")?;
+        writeln!(writer, "<title>{}</title>", escape(&self.title))?;
+        writeln!(writer, "<style>\n{}</style>", stylesheet())?;
+        writeln!(writer, "</head>")?;
+        writeln!(writer, "<body>")?;
+        writeln!(writer, "<pre>")
+    }
+
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        let addr = match self.uppercase {
+            true => format!("0x{addr:0width$X}", width = self.addr_width),
+            false => format!("0x{addr:0width$x}", width = self.addr_width),
+        };
+        let hex: Vec<_> = bytes
+            .iter()
+            .map(|&byte| {
+                let hex = match self.uppercase {
+                    true => format!("{byte:02X}"),
+                    false => format!("{byte:02x}"),
+                };
+                span(ByteClass::of(byte).name(), &hex)
+            })
+            .collect();
+        let ascii: String = bytes
+            .iter()
+            .map(|&byte| span(ByteClass::of(byte).name(), &(self.glyph)(byte)))
+            .collect();
+        let extra_space = " ".repeat((self.bytes_per_line - bytes.len()) * 3);
+        let split = span("dim", "│");
+
+        writeln!(
+            writer,
+            " {} {split} {}{extra_space} {split} {ascii}",
+            span("dim", &addr),
+            hex.join(" ")
+        )
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        writeln!(writer, "</pre>")?;
+        writeln!(writer, "</body>")?;
+        writeln!(writer, "</html>")
+    }
+}
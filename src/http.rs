@@ -0,0 +1,440 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    process::{Child, ChildStdout, Command, Stdio},
+};
+
+const MAX_REDIRECTS: usize = 5;
+
+/// Whether `input` names a URL rather than a local file
+pub fn is_url(input: &str) -> bool {
+    input.starts_with("http://") || input.starts_with("https://")
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+struct Url {
+    /// Whether the URL is HTTPS, which is fetched through curl
+    tls: bool,
+    /// Host as in the URL, an IPv6 address in brackets
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Url {
+    fn parse(url: &str) -> io::Result<Self> {
+        let (tls, rest) = match url.strip_prefix("https://") {
+            Some(rest) => (true, rest),
+            None => (
+                false,
+                url.strip_prefix("http://")
+                    .ok_or_else(|| invalid(format!("Not an HTTP URL: {url}")))?,
+            ),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        // Bracketed IPv6 addresses contain colons of their own
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => {
+                let port = port
+                    .parse()
+                    .map_err(|_| invalid(format!("Invalid port in URL: {url}")))?;
+                (host, port)
+            }
+            _ => (authority, if tls { 443 } else { 80 }),
+        };
+
+        Ok(Url {
+            tls,
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    fn scheme(&self) -> &'static str {
+        match self.tls {
+            true => "https",
+            false => "http",
+        }
+    }
+
+    /// Host to connect to, without the brackets of an IPv6 address
+    fn address(&self) -> &str {
+        match self.host.strip_prefix('[') {
+            Some(address) => address.strip_suffix(']').unwrap_or(address),
+            None => &self.host,
+        }
+    }
+
+    /// URL that `location` refers to, from the response to a request for this one
+    fn join(&self, location: &str) -> String {
+        if is_url(location) {
+            return location.to_string();
+        }
+        if let Some(rest) = location.strip_prefix("//") {
+            return format!("{}://{rest}", self.scheme());
+        }
+        let path = match location.chars().next() {
+            Some('/') => location.to_string(),
+            // A query goes on the same path, anything else next to its last segment
+            Some('?') => self.path.split('?').next().unwrap_or_default().to_string() + location,
+            _ => {
+                let path = self.path.split('?').next().unwrap_or_default();
+                let dir = &path[..path.rfind('/').map_or(0, |index| index + 1)];
+                format!("{dir}{location}")
+            }
+        };
+        format!(
+            "{}://{}:{}{}",
+            self.scheme(),
+            self.host,
+            self.port,
+            remove_dot_segments(&path)
+        )
+    }
+}
+
+/// `path` without the `.` and `..` segments, which a relative location may have
+fn remove_dot_segments(path: &str) -> String {
+    let (path, query) = match path.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path, None),
+    };
+    let mut segments: Vec<&str> = Vec::new();
+    let mut parts = path.split('/').skip(1).peekable();
+    while let Some(segment) = parts.next() {
+        let last = parts.peek().is_none();
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+        // A path ending in a dot segment still names a directory
+        if last && matches!(segment, "." | "..") {
+            segments.push("");
+        }
+    }
+    let path = format!("/{}", segments.join("/"));
+    match query {
+        Some(query) => format!("{path}?{query}"),
+        None => path,
+    }
+}
+
+/// Response to an HTTPS request, written by curl with its head first, as TLS is more than
+/// this crate implements. Curl already decodes a chunked body
+struct Curl {
+    child: Child,
+    output: BufReader<ChildStdout>,
+}
+
+impl Curl {
+    fn get(url: &str, range: Option<&str>) -> io::Result<Self> {
+        let mut command = Command::new("curl");
+        command.args([
+            "--silent",
+            "--show-error",
+            "--include",
+            "--user-agent",
+            "hex-thing",
+        ]);
+        if let Some(range) = range {
+            command.args(["--header", &format!("Range: {range}")]);
+        }
+        let mut child = command
+            .arg(url)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("HTTPS URLs are fetched with curl, which couldn't be run: {e}"),
+                )
+            })?;
+        let output = BufReader::new(child.stdout.take().expect("the stdout of curl is piped"));
+        Ok(Curl { child, output })
+    }
+}
+
+impl Read for Curl {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.output.read(buf)
+    }
+}
+
+impl BufRead for Curl {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.output.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.output.consume(amount)
+    }
+}
+
+impl Drop for Curl {
+    fn drop(&mut self) {
+        // A dump can stop before the end of the body, which curl would go on sending
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A file served over HTTP, fetched lazily so a skip can become a Range request
+pub struct HttpReader {
+    url: String,
+    offset: u64,
+    /// Offset the dump stops at, if it doesn't go on to the end of the file
+    end: Option<u64>,
+    body: Option<Box<dyn Read>>,
+}
+
+impl HttpReader {
+    pub fn new(url: &str) -> io::Result<Self> {
+        // Surface malformed and unsupported URLs before anything is read
+        Url::parse(url)?;
+        Ok(HttpReader {
+            url: url.to_string(),
+            offset: 0,
+            end: None,
+            body: None,
+        })
+    }
+
+    /// Only asks for the bytes up to `end` once something is read
+    pub fn limit(&mut self, end: u64) {
+        self.end = Some(end);
+    }
+
+    /// Skips `count` bytes, asking the server to start there if nothing has been read yet
+    pub fn skip(&mut self, count: u64) -> io::Result<()> {
+        match self.body {
+            None => {
+                self.offset += count;
+                Ok(())
+            }
+            Some(_) => io::copy(&mut self.take(count), &mut io::sink()).map(|_| ()),
+        }
+    }
+
+    fn request(&self) -> io::Result<Box<dyn Read>> {
+        let mut url = self.url.clone();
+        // Range ends are inclusive
+        let range = match (self.offset, self.end) {
+            (offset, Some(end)) if end > offset => Some(format!("bytes={offset}-{}", end - 1)),
+            (0, _) => None,
+            (offset, _) => Some(format!("bytes={offset}-")),
+        };
+
+        for _ in 0..MAX_REDIRECTS {
+            let parsed = Url::parse(&url)?;
+            let mut reader: Box<dyn BufRead> = match parsed.tls {
+                true => Box::new(Curl::get(&url, range.as_deref())?),
+                false => {
+                    let stream = TcpStream::connect((parsed.address(), parsed.port))?;
+                    let mut request = format!(
+                        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: hex-thing\r\nConnection: close\r\n",
+                        parsed.path, parsed.host
+                    );
+                    if let Some(range) = &range {
+                        request += &format!("Range: {range}\r\n");
+                    }
+                    request += "\r\n";
+                    (&stream).write_all(request.as_bytes())?;
+                    Box::new(BufReader::new(stream))
+                }
+            };
+            // Curl has already said what went wrong when it writes nothing
+            let (status, headers) = read_head(&mut reader).map_err(|e| match parsed.tls {
+                true => invalid(format!("Couldn't fetch {url} with curl")),
+                false => e,
+            })?;
+            let header = |name: &str| {
+                headers
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                    .map(|(_, value)| value.as_str())
+            };
+
+            let mut body: Box<dyn Read> = match header("Transfer-Encoding") {
+                Some(encoding) if encoding.eq_ignore_ascii_case("chunked") && !parsed.tls => {
+                    Box::new(ChunkedReader::new(reader))
+                }
+                _ => match header("Content-Length").and_then(|len| len.parse().ok()) {
+                    Some(len) => Box::new(reader.take(len)),
+                    None => Box::new(reader),
+                },
+            };
+
+            match status {
+                206 => return Ok(body),
+                200 => {
+                    // The server ignored the range, so skip to the offset ourselves
+                    io::copy(&mut (&mut body).take(self.offset), &mut io::sink())?;
+                    return Ok(body);
+                }
+                // Asked to start past the end of the file
+                416 => return Ok(Box::new(io::empty())),
+                301 | 302 | 303 | 307 | 308 => {
+                    let location = header("Location")
+                        .ok_or_else(|| invalid(format!("Redirect without a location: {url}")))?;
+                    url = parsed.join(location);
+                }
+                _ => return Err(invalid(format!("HTTP error {status} for {url}"))),
+            }
+        }
+
+        Err(invalid(format!("Too many redirects for {}", self.url)))
+    }
+}
+
+impl Read for HttpReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.body.is_none() {
+            self.body = Some(self.request()?);
+        }
+        self.body
+            .as_mut()
+            .expect("body was just requested")
+            .read(buf)
+    }
+}
+
+/// Reads the status code and headers of a response
+fn read_head(reader: &mut impl BufRead) -> io::Result<(u16, Vec<(String, String)>)> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| invalid(format!("Malformed HTTP status line: {}", line.trim())))?;
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    Ok((status, headers))
+}
+
+/// Decodes a body sent with `Transfer-Encoding: chunked`
+struct ChunkedReader<R> {
+    inner: R,
+    remaining: u64,
+    done: bool,
+}
+
+impl<R: BufRead> ChunkedReader<R> {
+    fn new(inner: R) -> Self {
+        ChunkedReader {
+            inner,
+            remaining: 0,
+            done: false,
+        }
+    }
+
+    fn next_chunk(&mut self) -> io::Result<()> {
+        let mut line = String::new();
+        self.inner.read_line(&mut line)?;
+        // Chunk extensions follow a semicolon
+        let size = line.split(';').next().unwrap_or_default().trim();
+        self.remaining = u64::from_str_radix(size, 16)
+            .map_err(|_| invalid(format!("Malformed chunk size: {size}")))?;
+
+        if self.remaining == 0 {
+            self.done = true;
+            // Skip any trailers up to the final empty line
+            loop {
+                line.clear();
+                if self.inner.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 && !self.done {
+            self.next_chunk()?;
+        }
+        if self.done || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let wanted = buf.len().min(self.remaining as usize);
+        let read = self.inner.read(&mut buf[..wanted])?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.remaining -= read as u64;
+
+        if self.remaining == 0 {
+            // Each chunk's data is followed by a CRLF
+            let mut crlf = String::new();
+            self.inner.read_line(&mut crlf)?;
+        }
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipv6_hosts_connect_without_brackets() {
+        let url = Url::parse("http://[::1]:8080/file").unwrap();
+        assert_eq!(
+            (url.host.as_str(), url.address(), url.port),
+            ("[::1]", "::1", 8080)
+        );
+        let url = Url::parse("http://[fe80::1]/").unwrap();
+        assert_eq!((url.address(), url.port), ("fe80::1", 80));
+    }
+
+    #[test]
+    fn redirects_are_resolved_against_the_request() {
+        let url = Url::parse("http://example.com/files/old/image.bin?v=1").unwrap();
+        let cases = [
+            (
+                "https://mirror.org/image.bin",
+                "https://mirror.org/image.bin",
+            ),
+            ("//mirror.org/image.bin", "http://mirror.org/image.bin"),
+            ("/new/image.bin", "http://example.com:80/new/image.bin"),
+            ("image.bin.1", "http://example.com:80/files/old/image.bin.1"),
+            (
+                "../new/image.bin",
+                "http://example.com:80/files/new/image.bin",
+            ),
+            ("./", "http://example.com:80/files/old/"),
+            ("..", "http://example.com:80/files/"),
+            ("?v=2", "http://example.com:80/files/old/image.bin?v=2"),
+        ];
+        for (location, expected) in cases {
+            assert_eq!(url.join(location), expected, "{location}");
+        }
+        let url = Url::parse("http://[::1]:8080/a/b").unwrap();
+        assert_eq!(url.join("c"), "http://[::1]:8080/a/c");
+    }
+}
@@ -0,0 +1,205 @@
+use std::io::{self, Write};
+
+use crate::{image::Image, render::Renderer};
+
+const DATA: u8 = 0x00;
+const END_OF_FILE: u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const START_LINEAR_ADDRESS: u8 = 0x05;
+
+fn invalid(line: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid Intel HEX record on line {line}: {message}"),
+    )
+}
+
+/// Decodes the hex digits of a record, checking its length and checksum
+fn decode_record(record: &str, line: usize) -> io::Result<Vec<u8>> {
+    let bytes = hex::decode(record).map_err(|_| invalid(line, "not hex digits"))?;
+    // Byte count, two address bytes, record type and checksum
+    if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+        return Err(invalid(line, "wrong byte count"));
+    }
+    if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+        return Err(invalid(line, "checksum mismatch"));
+    }
+    Ok(bytes)
+}
+
+/// Assembles the image described by the records of an Intel HEX file
+pub fn parse(text: &[u8]) -> io::Result<Image> {
+    let text = String::from_utf8_lossy(text);
+    let mut records = Vec::new();
+    // Added to every record's address by extended address records
+    let mut base = 0u64;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = line
+            .strip_prefix(':')
+            .ok_or_else(|| invalid(line_number, "missing ':' start code"))?;
+
+        let bytes = decode_record(record, line_number)?;
+        let address = u16::from_be_bytes([bytes[1], bytes[2]]) as u64;
+        let data = &bytes[4..bytes.len() - 1];
+
+        match bytes[3] {
+            DATA => records.push((base + address, data.to_vec())),
+            END_OF_FILE => break,
+            EXTENDED_SEGMENT_ADDRESS | EXTENDED_LINEAR_ADDRESS if data.len() != 2 => {
+                return Err(invalid(line_number, "extended address must be 2 bytes"));
+            }
+            EXTENDED_SEGMENT_ADDRESS => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as u64) << 4;
+            }
+            EXTENDED_LINEAR_ADDRESS => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as u64) << 16;
+            }
+            // Entry points don't affect the image
+            START_SEGMENT_ADDRESS | START_LINEAR_ADDRESS => {}
+            record_type => {
+                return Err(invalid(
+                    line_number,
+                    &format!("unknown record type {record_type:02x}"),
+                ));
+            }
+        }
+    }
+
+    Ok(Image::from_records(records))
+}
+
+/// Largest address records can reach through extended linear address records
+const MAX_ADDRESS: u64 = u32::MAX as u64;
+
+fn record(record_type: u8, address: u16, data: &[u8]) -> String {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend_from_slice(&address.to_be_bytes());
+    bytes.push(record_type);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(sum.wrapping_neg());
+    format!(":{}", hex::encode_upper(bytes))
+}
+
+/// Renders the bytes as Intel HEX records, loaded at `load_address` onwards
+pub struct Ihex {
+    record_size: usize,
+    load_address: u64,
+    /// Bytes waiting to fill a record, and the address of the first of them
+    pending: Vec<u8>,
+    pending_address: u64,
+    /// Upper 16 bits of the address set by the last extended linear address record
+    upper_address: u16,
+}
+
+impl Ihex {
+    pub fn new(record_size: usize, load_address: u64) -> Self {
+        Ihex {
+            record_size,
+            load_address,
+            pending: Vec::new(),
+            pending_address: 0,
+            upper_address: 0,
+        }
+    }
+
+    /// Writes records for `data`, which starts at `address`
+    fn write_records(
+        &mut self,
+        writer: &mut dyn Write,
+        mut address: u64,
+        mut data: &[u8],
+    ) -> io::Result<()> {
+        if address + data.len() as u64 > MAX_ADDRESS + 1 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Intel HEX can't hold data past address 0xffffffff",
+            ));
+        }
+        while !data.is_empty() {
+            let upper = (address >> 16) as u16;
+            if upper != self.upper_address {
+                writeln!(
+                    writer,
+                    "{}",
+                    record(EXTENDED_LINEAR_ADDRESS, 0, &upper.to_be_bytes())
+                )?;
+                self.upper_address = upper;
+            }
+            // A record can't cross into the next 64 KiB
+            let room = 0x10000 - (address & 0xffff);
+            let len = data.len().min(self.record_size).min(room as usize);
+            writeln!(writer, "{}", record(DATA, address as u16, &data[..len]))?;
+            address += len as u64;
+            data = &data[len..];
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        let pending = std::mem::take(&mut self.pending);
+        self.write_records(writer, self.pending_address, &pending)
+    }
+
+    fn push(&mut self, writer: &mut dyn Write, address: u64, bytes: &[u8]) -> io::Result<()> {
+        if self.pending_address + self.pending.len() as u64 != address {
+            self.flush(writer)?;
+            self.pending_address = address;
+        }
+        self.pending.extend_from_slice(bytes);
+
+        let full = self.pending.len() / self.record_size * self.record_size;
+        if full > 0 {
+            let rest = self.pending.split_off(full);
+            let pending = std::mem::replace(&mut self.pending, rest);
+            self.write_records(writer, self.pending_address, &pending)?;
+            self.pending_address += full as u64;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for Ihex {
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        self.push(writer, self.load_address + addr as u64, bytes)
+    }
+
+    fn collapses_holes(&self) -> bool {
+        true
+    }
+
+    /// Gaps between records stay gaps, while the zeros of sparse file holes are written out
+    fn hole(
+        &mut self,
+        writer: &mut dyn Write,
+        addr: usize,
+        len: usize,
+        is_gap: bool,
+    ) -> io::Result<()> {
+        if is_gap {
+            return Ok(());
+        }
+        let zeros = vec![0u8; self.record_size];
+        let mut address = self.load_address + addr as u64;
+        let end = address + len as u64;
+        while address < end {
+            let len = zeros.len().min((end - address) as usize);
+            self.push(writer, address, &zeros[..len])?;
+            address += len as u64;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        self.flush(writer)?;
+        writeln!(writer, "{}", record(END_OF_FILE, 0, &[]))
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    ops::Range,
+};
+
+/// Value read from addresses that no record covers, matching erased flash
+pub const GAP_FILL: u8 = 0xff;
+
+/// A sparse address space assembled from records, such as a firmware image
+pub struct Image {
+    /// Sorted, non-overlapping runs of bytes and the address each starts at
+    segments: Vec<(u64, Vec<u8>)>,
+    position: u64,
+}
+
+impl Image {
+    /// Assembles records of bytes at their addresses, later records overwriting earlier ones
+    pub fn from_records(mut records: Vec<(u64, Vec<u8>)>) -> Self {
+        records.sort_by_key(|(address, _)| *address);
+
+        let mut segments: Vec<(u64, Vec<u8>)> = Vec::new();
+        for (address, data) in records {
+            match segments.last_mut() {
+                Some((start, segment)) if address <= *start + segment.len() as u64 => {
+                    let offset = (address - *start) as usize;
+                    let overlap = (segment.len() - offset).min(data.len());
+                    segment[offset..offset + overlap].copy_from_slice(&data[..overlap]);
+                    segment.extend_from_slice(&data[overlap..]);
+                }
+                _ => segments.push((address, data)),
+            }
+        }
+
+        Image {
+            segments,
+            position: 0,
+        }
+    }
+
+    /// Address just past the last byte of the image
+    pub fn len(&self) -> u64 {
+        self.segments
+            .last()
+            .map_or(0, |(start, segment)| start + segment.len() as u64)
+    }
+
+    /// Address ranges before and between the records
+    pub fn gaps(&self) -> Vec<Range<u64>> {
+        let mut end = 0;
+        let mut gaps = Vec::new();
+        for (start, segment) in &self.segments {
+            if *start > end {
+                gaps.push(end..*start);
+            }
+            end = start + segment.len() as u64;
+        }
+        gaps
+    }
+}
+
+impl Read for Image {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let position = self.position;
+        let Some((start, segment)) = self
+            .segments
+            .iter()
+            .find(|(start, segment)| position < start + segment.len() as u64)
+        else {
+            return Ok(0);
+        };
+
+        let read = match position < *start {
+            true => {
+                let len = buf.len().min((start - position) as usize);
+                buf[..len].fill(GAP_FILL);
+                len
+            }
+            false => {
+                let data = &segment[(position - start) as usize..];
+                let len = buf.len().min(data.len());
+                buf[..len].copy_from_slice(&data[..len]);
+                len
+            }
+        };
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for Image {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len().checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Seek to a negative position in the image",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
@@ -0,0 +1,357 @@
+use std::io::{self, BufRead, Read};
+
+/// Size of the history a DEFLATE stream may refer back into
+const WINDOW_SIZE: usize = 32 * 1024;
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order in which the code length code lengths of a dynamic block are stored
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid deflate data: {message}"),
+    )
+}
+
+/// Reads a byte stream least significant bit first, as DEFLATE packs it
+struct BitReader<R> {
+    inner: R,
+    bits: u32,
+    count: u32,
+}
+
+impl<R: BufRead> BitReader<R> {
+    fn byte(&mut self) -> io::Result<u8> {
+        let byte = match self.inner.fill_buf()?.first() {
+            Some(&byte) => byte,
+            None => return Err(io::ErrorKind::UnexpectedEof.into()),
+        };
+        self.inner.consume(1);
+        Ok(byte)
+    }
+
+    fn bits(&mut self, count: u32) -> io::Result<u32> {
+        while self.count < count {
+            self.bits |= (self.byte()? as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.bits & ((1 << count) - 1);
+        self.bits >>= count;
+        self.count -= count;
+        Ok(value)
+    }
+
+    /// Drops the rest of the current partial byte
+    fn align(&mut self) {
+        self.bits = 0;
+        self.count = 0;
+    }
+}
+
+/// A canonical Huffman code, stored as the number of codes of each length
+/// and the symbols ordered by code
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> io::Result<Self> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(invalid("over-subscribed Huffman code"));
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn fixed() -> (Self, Self) {
+        let mut lengths = [0u8; 288];
+        lengths[..144].fill(8);
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        lengths[280..].fill(8);
+        let literals = Huffman::new(&lengths).expect("fixed literal code is valid");
+        let distances = Huffman::new(&[5; 30]).expect("fixed distance code is valid");
+        (literals, distances)
+    }
+
+    fn decode<R: BufRead>(&self, input: &mut BitReader<R>) -> io::Result<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= input.bits(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid("incomplete Huffman code"))
+    }
+}
+
+enum State {
+    BlockHeader,
+    Stored(usize),
+    Compressed(Box<(Huffman, Huffman)>),
+    Done,
+}
+
+/// Streaming decoder for raw DEFLATE data (RFC 1951)
+pub struct Inflate<R> {
+    input: BitReader<R>,
+    state: State,
+    last_block: bool,
+    window: Vec<u8>,
+    window_pos: usize,
+    /// Length and distance of a back reference that didn't fit in the last read
+    copy: (usize, usize),
+}
+
+impl<R: BufRead> Inflate<R> {
+    pub fn new(inner: R) -> Self {
+        Inflate {
+            input: BitReader {
+                inner,
+                bits: 0,
+                count: 0,
+            },
+            state: State::BlockHeader,
+            last_block: false,
+            window: vec![0; WINDOW_SIZE],
+            window_pos: 0,
+            copy: (0, 0),
+        }
+    }
+
+    /// Returns the underlying reader, positioned at the byte after the DEFLATE stream
+    pub fn into_inner(self) -> R {
+        self.input.inner
+    }
+
+    fn read_block_header(&mut self) -> io::Result<State> {
+        if self.last_block {
+            self.input.align();
+            return Ok(State::Done);
+        }
+
+        self.last_block = self.input.bits(1)? == 1;
+        match self.input.bits(2)? {
+            0 => {
+                self.input.align();
+                let len = self.input.bits(16)?;
+                let nlen = self.input.bits(16)?;
+                if len != !nlen & 0xffff {
+                    return Err(invalid("stored block length mismatch"));
+                }
+                Ok(State::Stored(len as usize))
+            }
+            1 => Ok(State::Compressed(Box::new(Huffman::fixed()))),
+            2 => Ok(State::Compressed(Box::new(self.read_dynamic_codes()?))),
+            _ => Err(invalid("reserved block type")),
+        }
+    }
+
+    fn read_dynamic_codes(&mut self) -> io::Result<(Huffman, Huffman)> {
+        let literal_count = self.input.bits(5)? as usize + 257;
+        let distance_count = self.input.bits(5)? as usize + 1;
+        let code_length_count = self.input.bits(4)? as usize + 4;
+
+        let mut code_lengths = [0u8; 19];
+        for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+            code_lengths[index] = self.input.bits(3)? as u8;
+        }
+        let code_length_code = Huffman::new(&code_lengths)?;
+
+        let mut lengths = Vec::with_capacity(literal_count + distance_count);
+        while lengths.len() < literal_count + distance_count {
+            let (value, repeat) = match code_length_code.decode(&mut self.input)? {
+                symbol @ 0..=15 => (symbol as u8, 1),
+                16 => match lengths.last() {
+                    Some(&previous) => (previous, 3 + self.input.bits(2)?),
+                    None => return Err(invalid("repeated length without a previous length")),
+                },
+                17 => (0, 3 + self.input.bits(3)?),
+                _ => (0, 11 + self.input.bits(7)?),
+            };
+            lengths.extend(std::iter::repeat_n(value, repeat as usize));
+        }
+        if lengths.len() > literal_count + distance_count {
+            return Err(invalid("too many code lengths"));
+        }
+        if lengths[256] == 0 {
+            return Err(invalid("missing end of block code"));
+        }
+
+        let literals = Huffman::new(&lengths[..literal_count])?;
+        let distances = Huffman::new(&lengths[literal_count..])?;
+        Ok((literals, distances))
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.window[self.window_pos % WINDOW_SIZE] = byte;
+        self.window_pos += 1;
+    }
+}
+
+impl<R: BufRead> Read for Inflate<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut written = 0;
+
+        while written < buf.len() {
+            if self.copy.0 > 0 {
+                let (length, distance) = self.copy;
+                let byte = self.window[(self.window_pos - distance) % WINDOW_SIZE];
+                self.push(byte);
+                buf[written] = byte;
+                written += 1;
+                self.copy.0 = length - 1;
+                continue;
+            }
+
+            self.state = match std::mem::replace(&mut self.state, State::Done) {
+                State::Done => break,
+                State::BlockHeader => self.read_block_header()?,
+                State::Stored(0) => State::BlockHeader,
+                State::Stored(remaining) => {
+                    let byte = self.input.bits(8)? as u8;
+                    self.push(byte);
+                    buf[written] = byte;
+                    written += 1;
+                    State::Stored(remaining - 1)
+                }
+                State::Compressed(codes) => {
+                    let (literals, distances) = codes.as_ref();
+                    let mut continue_block = true;
+                    match literals.decode(&mut self.input)? {
+                        symbol @ 0..=255 => {
+                            self.push(symbol as u8);
+                            buf[written] = symbol as u8;
+                            written += 1;
+                        }
+                        256 => continue_block = false,
+                        symbol @ 257..=285 => {
+                            let index = symbol as usize - 257;
+                            let length = LENGTH_BASE[index] as usize
+                                + self.input.bits(LENGTH_EXTRA[index] as u32)? as usize;
+                            let index = distances.decode(&mut self.input)? as usize;
+                            if index >= DIST_BASE.len() {
+                                return Err(invalid("invalid distance code"));
+                            }
+                            let distance = DIST_BASE[index] as usize
+                                + self.input.bits(DIST_EXTRA[index] as u32)? as usize;
+                            if distance > self.window_pos {
+                                return Err(invalid("distance too far back"));
+                            }
+                            self.copy = (length, distance);
+                        }
+                        _ => return Err(invalid("invalid literal/length code")),
+                    }
+                    match continue_block {
+                        true => State::Compressed(codes),
+                        false => State::BlockHeader,
+                    }
+                }
+            };
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, read_all};
+
+    const SAMPLE: &[u8] = include_bytes!("../tests/fixtures/sample.gz");
+    const NOISE: &[u8] = include_bytes!("../tests/fixtures/noise.gz");
+    const MEMBERS: &[u8] = include_bytes!("../tests/fixtures/members.gz");
+
+    /// The DEFLATE data of a gzip member without a file name, past its header
+    fn deflate(gzip: &[u8]) -> &[u8] {
+        &gzip[10..]
+    }
+
+    #[test]
+    fn inflates_dynamic_blocks_across_the_window() {
+        let data = read_all(Inflate::new(deflate(SAMPLE))).unwrap();
+        assert_eq!(data, fixtures::sample());
+    }
+
+    #[test]
+    fn inflates_stored_blocks() {
+        let data = read_all(Inflate::new(deflate(NOISE))).unwrap();
+        assert_eq!(data, fixtures::noise(4096));
+    }
+
+    #[test]
+    fn inflates_fixed_codes_and_stops_at_the_end() {
+        let mut inflate = Inflate::new(deflate(MEMBERS));
+        assert_eq!(read_all(&mut inflate).unwrap(), fixtures::SHORT);
+        let rest = inflate.into_inner();
+        // Only the trailer of the member is left before the next one
+        assert_eq!(&rest[8..11], &MEMBERS[..3]);
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let sample = &deflate(SAMPLE)[..SAMPLE.len() - 18];
+        for data in fixtures::truncations(sample, 127) {
+            assert!(
+                read_all(Inflate::new(data)).is_err(),
+                "{} bytes",
+                data.len()
+            );
+        }
+    }
+
+    #[test]
+    fn corrupt_data_never_panics() {
+        for data in fixtures::corruptions(deflate(SAMPLE), 127) {
+            let _ = read_all(Inflate::new(data.as_slice()));
+        }
+    }
+}
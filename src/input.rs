@@ -0,0 +1,225 @@
+use std::{
+    fs::{File, Metadata},
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    archive, base64,
+    decompress::{Compression, MAGIC_LEN},
+    escaped,
+    http::{self, HttpReader},
+    ihex,
+    image::Image,
+    memory::ProcessMemory,
+    srec,
+    util::read_full,
+};
+
+/// Path that selects standard input instead of a file
+pub const STDIN_PATH: &str = "-";
+
+/// How the bytes of an input encode the data to dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputFormat {
+    /// The bytes themselves
+    Raw,
+    /// Intel HEX records
+    Ihex,
+    /// Motorola S-records
+    Srec,
+    /// Base64 text, possibly wrapped over several lines
+    Base64,
+    /// Escaped bytes like `\x7f\x45` or `0x7f, 0x45`, as pasted from source code
+    Escaped,
+}
+
+/// A source of bytes to dump: a regular file, standard input, an HTTP URL,
+/// another process' memory, a decoded firmware image, or a stream derived from one of them
+pub enum Input {
+    File(File),
+    Stdin(io::Stdin),
+    Http(HttpReader),
+    Memory(ProcessMemory),
+    Image(Image),
+    /// Any other reader, along with its size if that is known
+    Stream(Box<dyn Read>, Option<u64>),
+}
+
+/// Reads a list of paths separated by newlines, or by NUL bytes when `null_separated`
+pub fn read_path_list(list: &Path, null_separated: bool) -> io::Result<Vec<PathBuf>> {
+    let mut contents = Vec::new();
+    Input::open(list)?.read_to_end(&mut contents)?;
+
+    let separator = if null_separated { b'\0' } else { b'\n' };
+    Ok(contents
+        .split(|&byte| byte == separator)
+        .map(|entry| match null_separated {
+            true => entry,
+            false => entry.strip_suffix(b"\r").unwrap_or(entry),
+        })
+        .filter(|entry| !entry.is_empty())
+        .map(path_from_bytes)
+        .collect())
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+impl Input {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if path.as_os_str() == STDIN_PATH {
+            Ok(Input::Stdin(io::stdin()))
+        } else if let Some(url) = path.to_str().filter(|path| http::is_url(path)) {
+            Ok(Input::Http(HttpReader::new(url)?))
+        } else {
+            Ok(Input::File(File::open(path)?))
+        }
+    }
+
+    /// Size of the input in bytes, if it can be known before reading it
+    pub fn size(&self) -> io::Result<Option<u64>> {
+        match self {
+            Input::File(file) => {
+                let metadata = file.metadata()?;
+                if is_block_device(&metadata) {
+                    // Block devices report a length of zero, but can be seeked to their end
+                    let mut file = file;
+                    let size = file.seek(SeekFrom::End(0))?;
+                    file.rewind()?;
+                    return Ok(Some(size));
+                }
+                // FIFOs, character devices and files in /proc report a length of zero too
+                Ok((metadata.is_file() && metadata.len() > 0).then_some(metadata.len()))
+            }
+            Input::Stdin(_) | Input::Http(_) | Input::Memory(_) => Ok(None),
+            Input::Image(image) => Ok(Some(image.len())),
+            Input::Stream(_, size) => Ok(*size),
+        }
+    }
+
+    /// Replaces a compressed input with its decompressed contents, leaving other inputs as is
+    pub fn decompressed(mut self) -> io::Result<Self> {
+        // Peek at regular files by rewinding them, so uncompressed ones stay seekable
+        if let Input::File(file) = &mut self {
+            if file.rewind().is_ok() {
+                let mut magic = [0u8; MAGIC_LEN];
+                let len = read_full(file, &mut magic)?;
+                file.rewind()?;
+                return match Compression::detect(&magic[..len]) {
+                    Some(compression) => Ok(Input::Stream(
+                        compression.decoder(BufReader::new(self))?,
+                        None,
+                    )),
+                    None => Ok(self),
+                };
+            }
+        }
+
+        let mut reader = BufReader::new(self);
+        match Compression::detect(reader.fill_buf()?) {
+            Some(compression) => Ok(Input::Stream(compression.decoder(reader)?, None)),
+            None => Ok(Input::Stream(Box::new(reader), None)),
+        }
+    }
+
+    /// Replaces a zip or tar archive with the contents of its member called `name`
+    pub fn archive_member(self, name: &str) -> io::Result<Self> {
+        let (member, size) = archive::open_member(BufReader::new(self), name)?;
+        Ok(Input::Stream(member, size))
+    }
+
+    /// An input over bytes already in memory
+    fn bytes(bytes: Vec<u8>) -> Self {
+        let size = bytes.len() as u64;
+        Input::Stream(Box::new(io::Cursor::new(bytes)), Some(size))
+    }
+
+    /// Replaces an encoded input with the data it describes
+    pub fn decoded(mut self, format: InputFormat) -> io::Result<Self> {
+        if format == InputFormat::Raw {
+            return Ok(self);
+        }
+        let mut text = Vec::new();
+        self.read_to_end(&mut text)?;
+        match format {
+            InputFormat::Raw => unreachable!("raw input is returned as is"),
+            InputFormat::Ihex => Ok(Input::Image(ihex::parse(&text)?)),
+            InputFormat::Srec => Ok(Input::Image(srec::parse(&text)?)),
+            InputFormat::Base64 => Ok(Input::bytes(base64::decode(&text)?)),
+            InputFormat::Escaped => Ok(Input::bytes(escaped::decode(&text)?)),
+        }
+    }
+
+    /// Tells the input nothing past `end` will be read, so a download can stop there
+    pub fn limit(&mut self, end: u64) {
+        if let Input::Http(http) = self {
+            http.limit(end);
+        }
+    }
+
+    /// Moves past the first `count` bytes, seeking when possible and reading otherwise
+    pub fn skip(&mut self, count: u64) -> io::Result<()> {
+        if let Input::Http(http) = self {
+            return http.skip(count);
+        }
+        if let Input::Memory(memory) = self {
+            return memory.seek(SeekFrom::Start(count)).map(|_| ());
+        }
+        if let Input::Image(image) = self {
+            return image.seek(SeekFrom::Start(count)).map(|_| ());
+        }
+        if let Input::File(file) = self {
+            if file.seek(SeekFrom::Start(count)).is_ok() {
+                return Ok(());
+            }
+        }
+        io::copy(&mut self.take(count), &mut io::sink()).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+fn is_block_device(metadata: &Metadata) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    metadata.file_type().is_block_device()
+}
+
+#[cfg(not(unix))]
+fn is_block_device(_metadata: &Metadata) -> bool {
+    false
+}
+
+impl Seek for Input {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Input::File(file) => file.seek(pos),
+            Input::Memory(memory) => memory.seek(pos),
+            Input::Image(image) => image.seek(pos),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Input can't be seeked",
+            )),
+        }
+    }
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Input::File(file) => file.read(buf),
+            Input::Stdin(stdin) => stdin.read(buf),
+            Input::Http(http) => http.read(buf),
+            Input::Memory(memory) => memory.read(buf),
+            Input::Image(image) => image.read(buf),
+            Input::Stream(stream, _) => stream.read(buf),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::io::{self, Write};
+
+use crate::render::{printable_char, Renderer};
+
+/// Quotes and escapes `text` as a JSON string
+pub fn string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\r' => quoted += "\\r",
+            '\t' => quoted += "\\t",
+            c if (c as u32) < 0x20 => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The fields describing a line of the dump
+fn row_fields(addr: usize, bytes: &[u8]) -> String {
+    let values: Vec<_> = bytes.iter().map(u8::to_string).collect();
+    let ascii: String = bytes.iter().map(|&byte| printable_char(byte)).collect();
+    format!(
+        "\"offset\": {addr}, \"bytes\": [{}], \"ascii\": {}",
+        values.join(", "),
+        string(&ascii)
+    )
+}
+
+/// Renders a JSON document with the rows of the dump, followed by a summary of what was dumped
+pub struct Json {
+    /// Name of the input, if it has one
+    file: Option<String>,
+    start: Option<usize>,
+    len: usize,
+    /// Whether the document is one in the array of several inputs, which ends its lines
+    in_array: bool,
+}
+
+impl Json {
+    pub fn new(file: Option<String>, in_array: bool) -> Self {
+        Json {
+            file,
+            in_array,
+            start: None,
+            len: 0,
+        }
+    }
+}
+
+impl Renderer for Json {
+    fn begin(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        let file = self.file.as_deref().map_or("null".to_string(), string);
+        writeln!(writer, "{{")?;
+        writeln!(writer, "  \"file\": {file},")?;
+        write!(writer, "  \"rows\": [")
+    }
+
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        // Separators are written ahead of every row but the first, as JSON has no trailing commas
+        let separator = match self.start {
+            Some(_) => ",",
+            None => "",
+        };
+        write!(writer, "{separator}\n    {{{}}}", row_fields(addr, bytes))?;
+        self.start.get_or_insert(addr);
+        self.len += bytes.len();
+        Ok(())
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, end: usize) -> io::Result<()> {
+        if self.start.is_some() {
+            write!(writer, "\n  ")?;
+        }
+        writeln!(writer, "],")?;
+        writeln!(writer, "  \"start\": {},", self.start.unwrap_or(end))?;
+        writeln!(writer, "  \"length\": {}", self.len)?;
+        match self.in_array {
+            true => write!(writer, "}}"),
+            false => writeln!(writer, "}}"),
+        }
+    }
+}
+
+/// Renders every line as a JSON object of its own line, for streaming into other tools
+pub struct JsonLines {
+    /// Name of the input, included in every object so several inputs can be told apart
+    file: Option<String>,
+}
+
+impl JsonLines {
+    pub fn new(file: Option<String>) -> Self {
+        JsonLines { file }
+    }
+}
+
+impl Renderer for JsonLines {
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        match &self.file {
+            Some(file) => writeln!(
+                writer,
+                "{{\"file\": {}, {}}}",
+                string(file),
+                row_fields(addr, bytes)
+            ),
+            None => writeln!(writer, "{{{}}}", row_fields(addr, bytes)),
+        }
+    }
+}
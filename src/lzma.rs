@@ -0,0 +1,513 @@
+use std::io::{self, Read};
+
+const NUM_STATES: usize = 12;
+const POS_BITS_MAX: usize = 4;
+const LEN_LOW_BITS: usize = 3;
+const LEN_MID_BITS: usize = 3;
+const LEN_HIGH_BITS: usize = 8;
+const NUM_LEN_TO_POS_STATES: usize = 4;
+const POS_SLOT_BITS: usize = 6;
+const END_POS_MODEL_INDEX: u32 = 14;
+const NUM_FULL_DISTANCES: usize = 1 << (END_POS_MODEL_INDEX >> 1);
+const ALIGN_BITS: usize = 4;
+const MATCH_MIN_LEN: usize = 2;
+
+/// Probability of a bit being 0, out of `1 << 11`, before anything was decoded
+const PROB_INIT: u16 = 1 << 10;
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid LZMA data: {message}"),
+    )
+}
+
+/// Range decoder over the compressed bytes of one LZMA2 chunk
+struct RangeDecoder<'a> {
+    input: &'a [u8],
+    range: u32,
+    code: u32,
+}
+
+impl<'a> RangeDecoder<'a> {
+    fn new(input: &'a [u8]) -> io::Result<Self> {
+        if input.len() < 5 || input[0] != 0 {
+            return Err(invalid("bad range coder start"));
+        }
+        let code = u32::from_be_bytes([input[1], input[2], input[3], input[4]]);
+        Ok(RangeDecoder {
+            input: &input[5..],
+            range: 0xffff_ffff,
+            code,
+        })
+    }
+
+    fn normalize(&mut self) -> io::Result<()> {
+        if self.range < 1 << 24 {
+            let (&byte, rest) = self
+                .input
+                .split_first()
+                .ok_or_else(|| invalid("chunk ends early"))?;
+            self.input = rest;
+            self.range <<= 8;
+            self.code = (self.code << 8) | byte as u32;
+        }
+        Ok(())
+    }
+
+    fn bit(&mut self, prob: &mut u16) -> io::Result<u32> {
+        let bound = (self.range >> 11) * *prob as u32;
+        let bit = match self.code < bound {
+            true => {
+                self.range = bound;
+                *prob += ((1 << 11) - *prob) >> 5;
+                0
+            }
+            false => {
+                self.range -= bound;
+                self.code -= bound;
+                *prob -= *prob >> 5;
+                1
+            }
+        };
+        self.normalize()?;
+        Ok(bit)
+    }
+
+    /// Bits with a fixed probability of one half
+    fn direct_bits(&mut self, count: usize) -> io::Result<u32> {
+        let mut value = 0;
+        for _ in 0..count {
+            self.range >>= 1;
+            let bit = (self.code >= self.range) as u32;
+            if bit == 1 {
+                self.code -= self.range;
+            }
+            self.normalize()?;
+            value = (value << 1) | bit;
+        }
+        Ok(value)
+    }
+
+    /// Whether every byte of the chunk was used, and the code came out where the encoder
+    /// flushed it, as it does unless the data is corrupt
+    fn is_finished(&self) -> bool {
+        self.input.is_empty() && self.code == 0
+    }
+
+    /// A `bits` long number coded most significant bit first with the probabilities of `probs`
+    fn tree(&mut self, probs: &mut [u16], bits: usize) -> io::Result<u32> {
+        let mut m = 1;
+        for _ in 0..bits {
+            m = (m << 1) | self.bit(&mut probs[m as usize])?;
+        }
+        Ok(m - (1 << bits))
+    }
+
+    /// A `bits` long number coded least significant bit first with the probabilities of `probs`
+    fn reverse_tree(&mut self, probs: &mut [u16], bits: usize) -> io::Result<u32> {
+        let mut m = 1;
+        let mut value = 0;
+        for index in 0..bits {
+            let bit = self.bit(&mut probs[m])?;
+            m = (m << 1) | bit as usize;
+            value |= bit << index;
+        }
+        Ok(value)
+    }
+}
+
+struct LenDecoder {
+    choice: u16,
+    choice2: u16,
+    low: [[u16; 1 << LEN_LOW_BITS]; 1 << POS_BITS_MAX],
+    mid: [[u16; 1 << LEN_MID_BITS]; 1 << POS_BITS_MAX],
+    high: [u16; 1 << LEN_HIGH_BITS],
+}
+
+impl LenDecoder {
+    fn new() -> Self {
+        LenDecoder {
+            choice: PROB_INIT,
+            choice2: PROB_INIT,
+            low: [[PROB_INIT; 1 << LEN_LOW_BITS]; 1 << POS_BITS_MAX],
+            mid: [[PROB_INIT; 1 << LEN_MID_BITS]; 1 << POS_BITS_MAX],
+            high: [PROB_INIT; 1 << LEN_HIGH_BITS],
+        }
+    }
+
+    fn decode(&mut self, rc: &mut RangeDecoder, pos_state: usize) -> io::Result<usize> {
+        if rc.bit(&mut self.choice)? == 0 {
+            return Ok(rc.tree(&mut self.low[pos_state], LEN_LOW_BITS)? as usize);
+        }
+        if rc.bit(&mut self.choice2)? == 0 {
+            let len = rc.tree(&mut self.mid[pos_state], LEN_MID_BITS)? as usize;
+            return Ok((1 << LEN_LOW_BITS) + len);
+        }
+        let len = rc.tree(&mut self.high, LEN_HIGH_BITS)? as usize;
+        Ok((1 << LEN_LOW_BITS) + (1 << LEN_MID_BITS) + len)
+    }
+}
+
+/// Literal context, literal position and position bits of an LZMA stream
+#[derive(Debug, Clone, Copy)]
+struct Properties {
+    lc: u32,
+    lp: u32,
+    pb: u32,
+}
+
+impl Properties {
+    fn parse(byte: u8) -> io::Result<Self> {
+        let byte = byte as u32;
+        let properties = Properties {
+            lc: byte % 9,
+            lp: (byte / 9) % 5,
+            pb: byte / 45,
+        };
+        match byte < 9 * 5 * 5 && properties.lc + properties.lp <= 4 {
+            true => Ok(properties),
+            false => Err(invalid("bad properties")),
+        }
+    }
+}
+
+/// Probabilities and recent distances of an LZMA stream, which LZMA2 chunks may keep
+struct State {
+    properties: Properties,
+    literals: Vec<u16>,
+    is_match: [u16; NUM_STATES << POS_BITS_MAX],
+    is_rep: [u16; NUM_STATES],
+    is_rep_g0: [u16; NUM_STATES],
+    is_rep_g1: [u16; NUM_STATES],
+    is_rep_g2: [u16; NUM_STATES],
+    is_rep0_long: [u16; NUM_STATES << POS_BITS_MAX],
+    pos_slot: [[u16; 1 << POS_SLOT_BITS]; NUM_LEN_TO_POS_STATES],
+    pos: [u16; 1 + NUM_FULL_DISTANCES - END_POS_MODEL_INDEX as usize],
+    align: [u16; 1 << ALIGN_BITS],
+    len: LenDecoder,
+    rep_len: LenDecoder,
+    state: usize,
+    reps: [usize; 4],
+}
+
+impl State {
+    fn new(properties: Properties) -> Self {
+        State {
+            properties,
+            literals: vec![PROB_INIT; 0x300 << (properties.lc + properties.lp)],
+            is_match: [PROB_INIT; NUM_STATES << POS_BITS_MAX],
+            is_rep: [PROB_INIT; NUM_STATES],
+            is_rep_g0: [PROB_INIT; NUM_STATES],
+            is_rep_g1: [PROB_INIT; NUM_STATES],
+            is_rep_g2: [PROB_INIT; NUM_STATES],
+            is_rep0_long: [PROB_INIT; NUM_STATES << POS_BITS_MAX],
+            pos_slot: [[PROB_INIT; 1 << POS_SLOT_BITS]; NUM_LEN_TO_POS_STATES],
+            pos: [PROB_INIT; 1 + NUM_FULL_DISTANCES - END_POS_MODEL_INDEX as usize],
+            align: [PROB_INIT; 1 << ALIGN_BITS],
+            len: LenDecoder::new(),
+            rep_len: LenDecoder::new(),
+            state: 0,
+            reps: [0; 4],
+        }
+    }
+
+    fn distance(&mut self, rc: &mut RangeDecoder, len: usize) -> io::Result<usize> {
+        let len_state = len.min(NUM_LEN_TO_POS_STATES - 1);
+        let slot = rc.tree(&mut self.pos_slot[len_state], POS_SLOT_BITS)?;
+        if slot < 4 {
+            return Ok(slot as usize);
+        }
+        let direct_bits = ((slot >> 1) - 1) as usize;
+        let mut distance = (2 | (slot & 1)) << direct_bits;
+        if slot < END_POS_MODEL_INDEX {
+            let probs = &mut self.pos[(distance - slot) as usize..];
+            distance += rc.reverse_tree(probs, direct_bits)?;
+        } else {
+            distance += rc.direct_bits(direct_bits - ALIGN_BITS)? << ALIGN_BITS;
+            distance += rc.reverse_tree(&mut self.align, ALIGN_BITS)?;
+        }
+        Ok(distance as usize)
+    }
+}
+
+/// Decoder of LZMA2 data, the filter xz compresses its blocks with
+pub struct Lzma2 {
+    /// The bytes decoded since the dictionary was last reset, as far back as it reaches
+    history: Vec<u8>,
+    /// Bytes decoded since the dictionary was last reset, also those no longer in `history`
+    total: usize,
+    dict_size: usize,
+    state: Option<State>,
+    /// Whether the data ended with its end marker
+    finished: bool,
+}
+
+impl Lzma2 {
+    pub fn new(dict_size: usize) -> Self {
+        Lzma2 {
+            history: Vec::new(),
+            total: 0,
+            dict_size,
+            state: None,
+            finished: false,
+        }
+    }
+
+    /// Dictionary size coded in the one byte of LZMA2 filter properties
+    pub fn dict_size(properties: u8) -> io::Result<usize> {
+        match properties {
+            40 => Ok(u32::MAX as usize),
+            0..40 => Ok((2 | (properties as usize & 1)) << (properties / 2 + 11)),
+            _ => Err(invalid("bad dictionary size")),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn push(&mut self, out: &mut Vec<u8>, byte: u8) {
+        self.history.push(byte);
+        out.push(byte);
+        self.total += 1;
+    }
+
+    fn byte_back(&self, distance: usize) -> u8 {
+        self.history[self.history.len() - distance]
+    }
+
+    /// Forgets what the dictionary no longer reaches, now and then so it stays cheap
+    fn trim(&mut self) {
+        if self.history.len() > 2 * self.dict_size.max(1 << 16) {
+            let excess = self.history.len() - self.dict_size;
+            self.history.drain(..excess);
+        }
+    }
+
+    /// Decodes the next chunk of `input` onto `out`
+    pub fn chunk(&mut self, input: &mut impl Read, out: &mut Vec<u8>) -> io::Result<()> {
+        let control = read_u8(input)?;
+        if control == 0 {
+            self.finished = true;
+            return Ok(());
+        }
+        // Dictionary resets come with the first chunk and whenever a chunk says so
+        if control == 1 || control >= 0xe0 {
+            self.history.clear();
+            self.total = 0;
+        }
+
+        if control < 0x80 {
+            if control > 2 {
+                return Err(invalid("bad chunk type"));
+            }
+            let size = read_u16_be(input)? as usize + 1;
+            let mut bytes = vec![0; size];
+            input.read_exact(&mut bytes)?;
+            self.history.extend_from_slice(&bytes);
+            self.total += size;
+            out.extend_from_slice(&bytes);
+            self.trim();
+            return Ok(());
+        }
+
+        let unpacked = (((control & 0x1f) as usize) << 16) + read_u16_be(input)? as usize + 1;
+        let packed = read_u16_be(input)? as usize + 1;
+        match (control >> 5) & 3 {
+            0 => {}
+            1 => {
+                let properties = self
+                    .state
+                    .as_ref()
+                    .ok_or_else(|| invalid("state reset without properties"))?
+                    .properties;
+                self.state = Some(State::new(properties));
+            }
+            _ => self.state = Some(State::new(Properties::parse(read_u8(input)?)?)),
+        }
+        let mut data = vec![0; packed];
+        input.read_exact(&mut data)?;
+
+        let mut state = self
+            .state
+            .take()
+            .ok_or_else(|| invalid("chunk without properties"))?;
+        let result = self.lzma(&mut state, &data, unpacked, out);
+        self.state = Some(state);
+        self.trim();
+        result
+    }
+
+    /// Decodes `unpacked` bytes of LZMA coded `data` onto `out`
+    fn lzma(
+        &mut self,
+        s: &mut State,
+        data: &[u8],
+        unpacked: usize,
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        let mut rc = RangeDecoder::new(data)?;
+        let Properties { lc, lp, pb } = s.properties;
+        let end = self.total + unpacked;
+
+        while self.total < end {
+            let pos_state = self.total & ((1 << pb) - 1);
+            let state = s.state;
+
+            if rc.bit(&mut s.is_match[(state << POS_BITS_MAX) + pos_state])? == 0 {
+                let previous = match self.history.is_empty() {
+                    true => 0,
+                    false => self.byte_back(1) as usize,
+                };
+                let lit_state = ((self.total & ((1 << lp) - 1)) << lc) + (previous >> (8 - lc));
+                let probs = &mut s.literals[0x300 * lit_state..0x300 * (lit_state + 1)];
+                let mut symbol = 1usize;
+                if state >= 7 {
+                    // After a match, the byte at the last distance predicts this one
+                    let mut match_byte = self.byte_back(s.reps[0] + 1) as usize;
+                    while symbol < 0x100 {
+                        let match_bit = (match_byte >> 7) & 1;
+                        match_byte <<= 1;
+                        let bit = rc.bit(&mut probs[((1 + match_bit) << 8) + symbol])? as usize;
+                        symbol = (symbol << 1) | bit;
+                        if match_bit != bit {
+                            break;
+                        }
+                    }
+                }
+                while symbol < 0x100 {
+                    symbol = (symbol << 1) | rc.bit(&mut probs[symbol])? as usize;
+                }
+                self.push(out, (symbol - 0x100) as u8);
+                s.state = match state {
+                    0..4 => 0,
+                    4..10 => state - 3,
+                    _ => state - 6,
+                };
+                continue;
+            }
+
+            let len = match rc.bit(&mut s.is_rep[state])? {
+                1 => {
+                    if self.history.is_empty() {
+                        return Err(invalid("repeated match without history"));
+                    }
+                    if rc.bit(&mut s.is_rep_g0[state])? == 0 {
+                        if rc.bit(&mut s.is_rep0_long[(state << POS_BITS_MAX) + pos_state])? == 0 {
+                            // A single byte at the last distance
+                            s.state = if state < 7 { 9 } else { 11 };
+                            let byte = self.byte_back(s.reps[0] + 1);
+                            self.push(out, byte);
+                            continue;
+                        }
+                    } else {
+                        let distance = match rc.bit(&mut s.is_rep_g1[state])? {
+                            0 => s.reps[1],
+                            _ => {
+                                let distance = match rc.bit(&mut s.is_rep_g2[state])? {
+                                    0 => s.reps[2],
+                                    _ => {
+                                        let distance = s.reps[3];
+                                        s.reps[3] = s.reps[2];
+                                        distance
+                                    }
+                                };
+                                s.reps[2] = s.reps[1];
+                                distance
+                            }
+                        };
+                        s.reps[1] = s.reps[0];
+                        s.reps[0] = distance;
+                    }
+                    s.state = if state < 7 { 8 } else { 11 };
+                    s.rep_len.decode(&mut rc, pos_state)?
+                }
+                _ => {
+                    s.reps[3] = s.reps[2];
+                    s.reps[2] = s.reps[1];
+                    s.reps[1] = s.reps[0];
+                    let len = s.len.decode(&mut rc, pos_state)?;
+                    s.state = if state < 7 { 7 } else { 10 };
+                    s.reps[0] = s.distance(&mut rc, len)?;
+                    if s.reps[0] == u32::MAX as usize {
+                        return Err(invalid("end marker in an LZMA2 chunk"));
+                    }
+                    len
+                }
+            };
+
+            let distance = s.reps[0] + 1;
+            if distance > self.history.len() || distance > self.dict_size {
+                return Err(invalid("match reaches past the dictionary"));
+            }
+            let len = (len + MATCH_MIN_LEN).min(end - self.total);
+            for _ in 0..len {
+                let byte = self.byte_back(distance);
+                self.push(out, byte);
+            }
+        }
+        match rc.is_finished() {
+            true => Ok(()),
+            false => Err(invalid("chunk doesn't end with its data")),
+        }
+    }
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_u16_be(reader: &mut impl Read) -> io::Result<u16> {
+    let mut bytes = [0u8; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    /// Raw LZMA2 data with a dictionary of 64 KiB
+    const SAMPLE: &[u8] = include_bytes!("../tests/fixtures/sample.lzma2");
+
+    fn decode(mut data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut lzma = Lzma2::new(64 << 10);
+        let mut out = Vec::new();
+        while !lzma.is_finished() {
+            lzma.chunk(&mut data, &mut out)?;
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn decodes_compressed_and_uncompressed_chunks() {
+        assert_eq!(decode(SAMPLE).unwrap(), fixtures::sample());
+    }
+
+    #[test]
+    fn dictionary_sizes_of_properties() {
+        assert_eq!(Lzma2::dict_size(0).unwrap(), 4 << 10);
+        assert_eq!(Lzma2::dict_size(1).unwrap(), 6 << 10);
+        assert_eq!(Lzma2::dict_size(18).unwrap(), 2 << 20);
+        assert_eq!(Lzma2::dict_size(40).unwrap(), u32::MAX as usize);
+        assert!(Lzma2::dict_size(41).is_err());
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        for data in fixtures::truncations(SAMPLE, 59) {
+            assert!(decode(data).is_err(), "{} bytes", data.len());
+        }
+    }
+
+    #[test]
+    fn corrupt_data_never_panics() {
+        for data in fixtures::corruptions(SAMPLE, 59) {
+            let _ = decode(&data);
+        }
+    }
+}
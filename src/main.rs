@@ -1,32 +1,275 @@
 use std::{
-    fs::File,
-    io::{self, BufRead, BufReader, BufWriter, Read, Seek, Write},
-    path::PathBuf,
+    collections::VecDeque,
+    env,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
 
+use annotation::Region;
 use byte_range::ByteRange;
-use clap::Parser;
+use charset::{Charset, TextByte};
+use checksum::LineChecksum;
+use clap::{Parser, Subcommand, ValueEnum};
+use code::{Code, Lang, Visibility};
 use colored::{Color, Colorize};
+use csv::Csv;
+use escaped::Escaped;
+use graphics::Protocol;
+use hexdump::Hexdump;
+use html::Html;
+use ihex::Ihex;
+use image::GAP_FILL;
+use input::{Input, InputFormat};
+use json::{Json, JsonLines};
 use lazy_static::lazy_static;
-use util::{parse_num, ApplyIf};
+use mark::Mark;
+use markdown::Markdown;
+use memory::ProcessMemory;
+use mmap::Mmap;
+use od::Od;
+use overview::Overview;
+use pager::Pager;
+use plain::Plain;
+use radix::Radix;
+use render::{ByteClass, ClassCounts, ColorBy, Format, Renderer, Squeeze, Style};
+use srec::Srec;
+use svg::Svg;
+use template::Template;
+use theme::{ColorMap, Config, Highlights, Paint, Theme};
+use uf2::Uf2;
+use util::{parse_num, read_full, ApplyIf, LineLimit};
+use visualize::{Layout, Picture, Plot};
+use words::{Endian, Float, Word};
+use xxd::Xxd;
 
+mod annotation;
+mod archive;
+mod base64;
 mod byte_range;
+mod charset;
+mod checksum;
+mod code;
+mod codepage;
+mod csv;
+mod decompress;
+mod entropy;
+mod escaped;
+#[cfg(test)]
+mod fixtures;
+mod graphics;
+mod hexdump;
+mod html;
+mod http;
+mod ihex;
+mod image;
+mod inflate;
+mod input;
+mod json;
+mod lzma;
+mod mark;
+mod markdown;
+mod memory;
+mod mmap;
+mod od;
+mod overview;
+mod pager;
+mod plain;
+mod png;
+mod radix;
+mod render;
+mod resume;
+mod serial;
+mod socket;
+mod sparse;
+mod srec;
+mod svg;
+mod template;
+mod terminal;
+mod theme;
+mod uf2;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+mod uring;
 mod util;
+mod visualize;
+mod walk;
+mod words;
+mod xxd;
+mod zstd;
 
 #[derive(Parser, Debug)]
 #[clap(name = "hex-thing", about = "A custom hex dump tool", version = "1.0")]
 struct Args {
-    /// Input file to process
-    #[clap(value_name = "FILE")]
-    input: PathBuf,
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// Input files or HTTP URLs (HTTPS ones fetched through curl) to process, or "-" to read
+    /// from standard input
+    #[clap(value_name = "FILE", default_value = input::STDIN_PATH)]
+    input: Vec<PathBuf>,
+
+    /// Keep reading the (last) input as it grows, like `tail -f`
+    #[clap(short, long, conflicts_with = "reverse")]
+    follow: bool,
+
+    /// Clear the screen and dump the input again whenever it changes
+    #[clap(short, long, conflicts_with_all = ["reverse", "follow", "output"])]
+    watch: bool,
+
+    /// Decompress gzip, xz or zstd input before dumping it. Files and standard input starting
+    /// with the magic bytes of one are decompressed anyway, unless followed
+    #[clap(short = 'z', long)]
+    decompress: bool,
+
+    /// Dump compressed files and standard input as they are, rather than decompressing them
+    #[clap(long, conflicts_with = "decompress")]
+    no_decompress: bool,
+
+    /// Dump the member called NAME of a zip or tar archive instead of the archive itself
+    #[clap(long, value_name = "NAME", conflicts_with = "reverse")]
+    archive_member: Option<String>,
+
+    /// How the input encodes the bytes to dump, e.g. ihex or srec to rebuild the image of a firmware file
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        value_enum,
+        default_value = "raw",
+        conflicts_with = "reverse"
+    )]
+    input_format: InputFormat,
+
+    /// Listen on ADDR (host:port or unix:PATH) and dump the bytes of the first connection
+    #[clap(long, value_name = "ADDR", conflicts_with_all = ["input", "reverse", "watch", "connect"])]
+    listen: Option<String>,
+
+    /// Connect to ADDR (host:port or unix:PATH) and dump the bytes it sends
+    #[clap(long, value_name = "ADDR", conflicts_with_all = ["input", "reverse", "watch"])]
+    connect: Option<String>,
+
+    /// Dump bytes arriving on a serial DEVICE, with the time each line was received
+    #[clap(long, value_name = "DEVICE", conflicts_with_all = ["input", "reverse", "watch", "listen", "connect"])]
+    serial: Option<PathBuf>,
+
+    /// Baud rate of the serial device
+    #[clap(long, default_value = "115200", requires = "serial")]
+    baud: u32,
+
+    /// Dump the memory of the process with this PID (Linux only), usually with --byte-range
+    #[clap(long, value_name = "PID", conflicts_with_all = ["input", "reverse", "watch", "follow", "listen", "connect", "serial"])]
+    pid: Option<u32>,
+
+    /// Dump the holes of sparse files (and gaps of firmware images) in full instead of collapsing them
+    #[clap(long)]
+    expand_holes: bool,
+
+    /// Read the paths to dump from LIST, one per line ("-" reads them from standard input)
+    #[clap(long, value_name = "LIST", conflicts_with = "input")]
+    files_from: Option<PathBuf>,
+
+    /// Paths in the --files-from list are separated by NUL bytes instead of newlines
+    #[clap(short = '0', long, requires = "files_from")]
+    null: bool,
+
+    /// Dump every file below DIR
+    #[clap(short = 'R', long, value_name = "DIR", conflicts_with_all = ["input", "files_from"])]
+    recursive: Option<PathBuf>,
+
+    /// Only dump files of --recursive matching PATTERN (e.g. "*.bin"), can be repeated
+    #[clap(long, value_name = "PATTERN", requires = "recursive")]
+    glob: Vec<String>,
+
+    /// Memory map input files instead of reading them (done automatically for large files)
+    #[clap(long, conflicts_with = "follow")]
+    mmap: bool,
+
+    /// Record progress in STATEFILE, and continue from it if an earlier dump was interrupted.
+    /// Only for the formats written line by line: text, jsonl, csv, tsv and markdown
+    #[clap(long, value_name = "STATEFILE", conflicts_with_all = ["reverse", "watch", "follow", "listen", "connect", "serial", "overview"])]
+    resume: Option<PathBuf>,
+
+    /// Print how many of the bytes shown are null, whitespace, printable, control or high,
+    /// after each dump
+    #[clap(long, conflicts_with_all = ["format", "include", "rust", "lang", "template"])]
+    stats: bool,
+
+    /// Print how many bytes were shown, from where to where and out of how many, after each dump
+    #[clap(long, conflicts_with_all = ["format", "include", "rust", "lang", "template"])]
+    summary: bool,
+
+    /// Don't print a filename header before each file when dumping several files
+    #[clap(long)]
+    no_header: bool,
 
     /// Output to a file instead of standard output
     #[clap(short, long, value_name = "OUTPUT")]
     output: Option<PathBuf>,
 
-    /// Number of bytes per line
-    #[clap(short = 'l', long, default_value = "16", value_parser = parse_num)]
-    bytes_per_line: usize,
+    /// Don't pipe output longer than the terminal through $PAGER (by default `less -R`)
+    #[clap(long)]
+    no_pager: bool,
+
+    /// Kind of output, e.g. json for one JSON document per input
+    #[clap(long, value_name = "FORMAT", value_enum, default_value = "text", conflicts_with_all = ["style", "plain", "reverse", "include", "rust", "lang", "template"])]
+    format: Format,
+
+    /// Write the bytes of --format escaped as 0x41 numbers with SEP between them, e.g. ", "
+    #[clap(long, value_name = "SEP")]
+    sep: Option<String>,
+
+    /// Bytes of data in each record of --format ihex or srec
+    #[clap(long, value_name = "N", default_value = "16", value_parser = clap::value_parser!(u8).range(1..))]
+    record_size: u8,
+
+    /// Address of the first byte of the input in --format ihex, srec or uf2
+    #[clap(long, visible_alias = "base-address", value_name = "ADDR", default_value = "0", value_parser = parse_num)]
+    load_address: usize,
+
+    /// Family of the board in --format uf2, e.g. 0xe48bff56 for the RP2040
+    #[clap(long, value_name = "ID", value_parser = parse_num)]
+    family_id: Option<usize>,
+
+    /// Layout of the dump, e.g. xxd, hexdump or od to match the output of those tools
+    #[clap(long, value_name = "STYLE", value_enum, default_value = "hex-thing")]
+    style: Style,
+
+    /// Print nothing but the hex digits, like `xxd -ps` (short for --style plain)
+    #[clap(short, long, conflicts_with = "style")]
+    plain: bool,
+
+    /// Write the bytes as a C array, like `xxd -i`
+    #[clap(short, long, conflicts_with_all = ["style", "plain", "reverse", "resume"])]
+    include: bool,
+
+    /// Write the bytes as a Rust constant
+    #[clap(long, conflicts_with_all = ["style", "plain", "reverse", "resume", "include"])]
+    rust: bool,
+
+    /// Write the bytes as a literal in the source code of LANG
+    #[clap(long, value_name = "LANG", value_enum, conflicts_with_all = ["style", "plain", "reverse", "resume", "include", "rust"])]
+    lang: Option<Lang>,
+
+    /// Visibility of the constant written for Rust, Java or C#
+    #[clap(long, value_name = "VISIBILITY", value_enum, default_value = "pub")]
+    visibility: Visibility,
+
+    /// Make the constant written for Rust a `&[u8]` slice instead of an array
+    #[clap(long)]
+    slice: bool,
+
+    /// Name of the constant written as code, instead of one derived from the file name
+    #[clap(long, value_name = "NAME", value_parser = code::parse_name)]
+    name: Option<String>,
+
+    /// Render each line with the template in FILE, with placeholders like {addr}, {hex} and {ascii}
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["style", "plain", "reverse", "include", "rust", "lang"])]
+    template: Option<PathBuf>,
+
+    /// Number of bytes per line, or auto to fit the terminal [default: 16, 30 in the plain style or 12 for code]
+    #[clap(short = 'l', long, value_name = "N", value_parser = parse_line_length)]
+    bytes_per_line: Option<LineLength>,
 
     /// Skip the N first bytes of the file
     #[clap(short, long, value_name = "N", value_parser = parse_num)]
@@ -36,229 +279,2269 @@ struct Args {
     #[clap(short = 'n', long, value_name = "N", value_parser = parse_num)]
     length: Option<usize>,
 
-    /// Byte range to read (e.g., 0-1000 or 0xff-0x3e7)
+    /// Stop after printing N lines, however many bytes they show, e.g. for a screenful
+    #[clap(long, value_name = "N", value_parser = parse_num, conflicts_with_all = ["format", "include", "rust", "lang", "template"])]
+    lines: Option<usize>,
+
+    /// Byte range to read (e.g., 0-1000 or 0xff-0x3e7, 0x100- to the end or -0x200 from the
+    /// start), repeated or separated by commas to read several with a marker for the bytes
+    /// between them
     #[clap(
         long,
         value_name = "RANGE",
+        value_delimiter = ',',
+        allow_hyphen_values = true,
         conflicts_with_all = ["skip", "length"],
     )]
-    byte_range: Option<ByteRange>,
+    byte_range: Vec<ByteRange>,
 
     /// Reverse operation (hexdump to binary)
     #[clap(short = 'r', long, requires = "output")]
     reverse: bool,
 
+    /// When to color the output: auto colors it only for a terminal, unless NO_COLOR or
+    /// CLICOLOR=0 is set, or always with CLICOLOR_FORCE
+    #[clap(long, value_name = "WHEN", value_enum, default_value = "auto")]
+    color: ColorChoice,
+
+    /// Colors of the default style: default, dark, light, mono, or a [theme NAME] section
+    /// of ~/.config/hex-thing/config, whose `theme = NAME` line picks one otherwise
+    #[clap(long, value_name = "NAME", conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    theme: Option<String>,
+
+    /// Change colors of the theme, like `printable=green,null=gray,high=magenta`. The classes
+    /// are null, whitespace, printable, control and high, with dim for addresses and bars,
+    /// and the colors are names, #rrggbb or none
+    #[clap(long, value_name = "MAP", value_parser = theme::parse_color_map, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    color_map: Option<ColorMap>,
+
+    /// Colors of byte values that should stand out, like `0x00=red,0xff=black/yellow`, with
+    /// the background after a `/`
+    #[clap(long, value_name = "COLORS", value_parser = theme::parse_highlights, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    highlight: Option<Highlights>,
+
+    /// Color a region like `0x100-0x11f:yellow`, at addresses as shown with both ends included,
+    /// labeling it after its first line with `0x100-0x11f:/blue:header`. Can be repeated
+    #[clap(long, value_name = "RANGE:COLOR[:LABEL]", conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    mark: Vec<Mark>,
+
+    /// Print a header like `── .text @ 0x1000 ──` where the dump gets to each region of FILE,
+    /// whose lines are like `0x1000-0x1fff .text` or `0x1000 .text`, at addresses as shown
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    annotate: Option<PathBuf>,
+
+    /// What the colors of the bytes show
+    #[clap(long, value_name = "BY", value_enum, default_value = "class", conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    color_by: ColorBy,
+
+    /// Base the bytes are shown in
+    #[clap(long, value_name = "RADIX", value_enum, default_value = "hex", conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    radix: Radix,
+
+    /// Add ADDR to every address shown, such as where a firmware image is loaded, like `xxd -o`
+    #[clap(long, value_name = "ADDR", default_value = "0", value_parser = parse_num)]
+    display_offset: usize,
+
+    /// Count addresses from the start of --byte-range or --skip rather than of the input
+    #[clap(long)]
+    relative: bool,
+
+    /// Pad addresses to N digits, rather than to as many as the last one shown needs
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["style", "plain", "include", "rust", "lang", "template"])]
+    addr_width: Option<u32>,
+
+    /// Follow every address with its decimal, like `0x00001000 (4096)`
+    #[clap(long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template", "no_addr"])]
+    addr_both: bool,
+
+    /// Base the addresses are shown in, dec matching the offsets of most specifications
+    /// (dec, oct and bin being short for decimal, octal and binary)
+    #[clap(long, value_name = "RADIX", value_enum, default_value = "hex", conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    addr_radix: Radix,
+
+    /// Group the bytes of the default style N at a time, like `xxd -g` [default: 1, or 4 with -e]
+    #[clap(short, long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    group: Option<u32>,
+
+    /// Show each group as a little-endian word, with its bytes swapped, like `xxd -e`
+    #[clap(short = 'e', long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    little_endian: bool,
+
+    /// Add a column with the bytes of each line read as unsigned integers of this size
+    #[clap(long, value_name = "TYPE", value_enum, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    words: Option<Word>,
+
+    /// Add a column with the bytes of each line read as floating point numbers of this size
+    #[clap(long, value_name = "TYPE", value_enum, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    floats: Option<Float>,
+
+    /// Show --words as signed, two's complement integers, as well as bytes of --radix decimal
+    #[clap(long)]
+    signed: bool,
+
+    /// Byte order of the --words and --floats
+    #[clap(long, value_name = "ENDIAN", value_enum, default_value = "little")]
+    endian: Endian,
+
+    /// Print a header with the offset of every column, repeated every N lines if given, e.g. --ruler=16
+    #[clap(long, value_name = "N", num_args = 0..=1, require_equals = true, default_missing_value = "0", value_parser = parse_num, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    ruler: Option<usize>,
+
+    /// Split the hex and text columns every N bytes, also when reversing
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    split_every: Option<u32>,
+
+    /// Print an empty line every N lines, e.g. to mark the sectors or pages of the input
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    gap_every: Option<u32>,
+
+    /// Start a new page with a form feed every N lines, for printing, each page with a ruler
+    /// and in a frame of its own with --border
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    paginate: Option<u32>,
+
+    /// Separator between the columns of the default style, such as `|` for fonts without
+    /// box drawing characters, or nothing at all. Reversing takes the same one
+    #[clap(long, value_name = "STR", default_value = "│", conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    separator: String,
+
+    /// Draw a frame around the dump, also when reversing
+    #[clap(long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    border: bool,
+
+    /// Show the Shannon entropy of every line after it, in bits per byte and as a bar, so
+    /// compressed or encrypted parts stand out
+    #[clap(long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    entropy: bool,
+
+    /// Show a braille sparkline of the values of the bytes of every line after it, two bytes
+    /// to a character, so repeating patterns stand out
+    #[clap(long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    density: bool,
+
+    /// Print a character for every N bytes instead of the dump, shaded by their entropy and
+    /// colored by their most common class, to find the parts of a large input worth dumping.
+    /// N fits the input in a few screens unless given, e.g. --overview=4096
+    #[clap(long, value_name = "N", num_args = 0..=1, require_equals = true, default_missing_value = "0", value_parser = parse_num, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    overview: Option<usize>,
+
+    /// Only dump the first of every N lines, with a marker for the bytes left out in between,
+    /// to skim an input too large to read through
+    #[clap(long, value_name = "N", value_parser = parse_num, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template", "overview"])]
+    sample: Option<usize>,
+
+    /// Show a checksum of every line after it, for checking dumps copied by hand
+    #[clap(long, value_name = "CHECKSUM", value_enum, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    line_checksum: Option<LineChecksum>,
+
+    /// Leave out the address column, also when reversing
+    #[clap(long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    no_addr: bool,
+
+    /// Leave out the text column, for a view of nothing but the bytes
+    #[clap(long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template"])]
+    no_ascii: bool,
+
+    /// Character set of the text column, whose glyphs for control characters and bytes with
+    /// no character a [glyphs] section of ~/.config/hex-thing/config can change, like
+    /// `control = .`
+    #[clap(long, value_name = "CHARSET", value_enum, default_value = "ascii", conflicts_with_all = ["style", "plain", "include", "rust", "lang", "template"])]
+    charset: Charset,
+
+    /// Replace runs of identical lines with a single `*`, like `xxd -a`, also when reversing
+    #[clap(short, long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template", "no_addr"])]
+    autoskip: bool,
+
+    /// Replace runs of identical lines with a count of them, such as `* (repeated 512 times)`
+    #[clap(long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template", "autoskip"])]
+    squeeze_count: bool,
+
+    /// Show only the text column, each character as wide as the digits of a byte,
+    /// for skimming mostly text formats
+    #[clap(long, conflicts_with_all = ["style", "plain", "format", "include", "rust", "lang", "template", "no_ascii", "little_endian", "reverse"])]
+    ascii_only: bool,
+
+    /// Show each byte as 8 binary digits instead of hex, like `xxd -b` (short for --radix binary)
+    #[clap(short, long, conflicts_with_all = ["radix", "style", "plain", "format", "include", "rust", "lang", "template"])]
+    bits: bool,
+
     /// Display hex in uppercase (e.g., 0xFF instead of 0xff)
     #[clap(short, long)]
     uppercase: bool,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Draw a PNG image of the whole input, by default with a pixel for each byte colored by its class
+    Visualize {
+        /// File to draw, or "-" to read from standard input
+        #[clap(value_name = "FILE", default_value = input::STDIN_PATH)]
+        input: PathBuf,
+
+        /// PNG file to write the image to
+        #[clap(short, long, value_name = "OUTPUT", required_unless_present = "inline")]
+        output: Option<PathBuf>,
+
+        /// Draw the image right in the terminal, with sixel or kitty graphics
+        #[clap(long, conflicts_with = "output")]
+        inline: bool,
+
+        /// Graphics protocol of --inline, instead of guessing the one the terminal supports
+        #[clap(long, value_name = "PROTOCOL", value_enum, requires = "inline")]
+        protocol: Option<Protocol>,
+
+        /// What the image shows
+        #[clap(long, value_name = "PLOT", value_enum, default_value = "byteview")]
+        plot: Plot,
+
+        /// Pixels in each row of the byteview, which has up to 4096 rows before its pixels
+        /// each show the average of several bytes
+        #[clap(long, value_name = "N", default_value = "256", value_parser = clap::value_parser!(u32).range(1..))]
+        width: u32,
+
+        /// Order in which the bytes fill the byteview
+        #[clap(long, value_name = "LAYOUT", value_enum, default_value = "linear")]
+        layout: Layout,
+    },
+}
+
 lazy_static! {
     static ref ARGS: Args = Args::parse();
-    static ref USE_COLOR: bool = ARGS.output.is_none();
-    static ref RAW_SPLIT_SYMBOL: &'static str = "│";
+    static ref USE_COLOR: bool = match ARGS.color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => auto_color(),
+    };
+    static ref RAW_SPLIT_SYMBOL: &'static str = ARGS.separator.as_str();
     static ref SPLIT_SYMBOL: String = match *USE_COLOR {
-        true => RAW_SPLIT_SYMBOL.color(Color::BrightBlack).to_string(),
+        true => paint(RAW_SPLIT_SYMBOL.to_string(), THEME.dim),
         false => RAW_SPLIT_SYMBOL.to_string(),
     };
-    static ref START: usize = match ARGS.byte_range.clone() {
+    static ref CONFIG: Config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    static ref THEME: Theme = match load_theme() {
+        Ok(theme) => theme,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    static ref STYLE: Style = match ARGS.plain {
+        true => Style::Plain,
+        false => ARGS.style,
+    };
+    static ref LANG: Option<Lang> = match (ARGS.include, ARGS.rust) {
+        (true, _) => Some(Lang::C),
+        (false, true) => Some(Lang::Rust),
+        (false, false) => ARGS.lang,
+    };
+    static ref BYTES_PER_LINE: usize = match (ARGS.bytes_per_line, *STYLE, *LANG) {
+        (Some(LineLength::Bytes(count)), _, _) => count,
+        (Some(LineLength::Auto), _, None) if ARGS.format == Format::Text => fitting_line_length(),
+        (_, _, Some(_)) => CODE_BYTES_PER_LINE,
+        (_, Style::Plain, None) => PLAIN_BYTES_PER_LINE,
+        _ => DEFAULT_BYTES_PER_LINE,
+    };
+    static ref RADIX: Radix = match ARGS.bits {
+        true => Radix::Binary,
+        false => ARGS.radix,
+    };
+    static ref GROUP: usize = ARGS.group.unwrap_or(match ARGS.little_endian {
+        true => LITTLE_ENDIAN_GROUP,
+        false => 1,
+    }) as usize;
+    static ref SPLIT_EVERY: Option<usize> = ARGS.split_every.map(|every| every as usize);
+    static ref RANGES: Vec<ByteRange> = byte_range::merged(&ARGS.byte_range);
+    static ref START: usize = match RANGES.first() {
         Some(range) => range.start,
-        None => match ARGS.skip {
-            Some(num) => num,
-            None => 0,
-        },
+        None => ARGS.skip.unwrap_or(0),
     };
-    static ref MAX_COUNT: Option<usize> = match ARGS.byte_range.clone() {
-        Some(range) => Some(range.end - range.start),
-        None => match ARGS.length {
-            Some(num) => Some(num),
-            None => None,
-        },
+    static ref MAX_COUNT: Option<usize> = match RANGES.last() {
+        Some(range) => range.end.map(|end| end - *START),
+        None => ARGS.length,
     };
+    /// Whether the dump may be of several inputs, which --format json puts in an array
+    static ref SEVERAL_INPUTS: bool =
+        ARGS.input.len() > 1 || ARGS.files_from.is_some() || ARGS.recursive.is_some();
 }
 
-fn get_color(byte: u8) -> Color {
-    match byte {
-        0 => Color::BrightBlack,
-        9 | 10 | 13 | 32 => Color::Cyan,
-        32..=126 => Color::Green,
-        128..=255 => Color::Yellow,
-        _ => Color::BrightRed,
+const DEFAULT_BYTES_PER_LINE: usize = 16;
+
+/// Line width of the plain style, which is that of `xxd -ps`
+const PLAIN_BYTES_PER_LINE: usize = 30;
+
+/// Bytes in the words of -e, which are those of `xxd -e`
+const LITTLE_ENDIAN_GROUP: u32 = 4;
+
+/// Elements per line of code, as written by `xxd -i`
+const CODE_BYTES_PER_LINE: usize = 12;
+
+/// Value of --bytes-per-line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineLength {
+    /// As many bytes as fit in the width of the terminal
+    Auto,
+    Bytes(usize),
+}
+
+fn parse_line_length(input: &str) -> Result<LineLength, std::num::ParseIntError> {
+    match input {
+        "auto" => Ok(LineLength::Auto),
+        _ => parse_num(input).map(LineLength::Bytes),
+    }
+}
+
+/// Whether --color auto colors the output. NO_COLOR turns colors off, then CLICOLOR_FORCE
+/// turns them on even away from a terminal, while CLICOLOR=0 turns them off for one,
+/// see https://no-color.org and https://bixense.com/clicolors
+fn auto_color() -> bool {
+    // An empty variable counts as unset
+    let var = |name| env::var(name).ok().filter(|value| !value.is_empty());
+    if var("NO_COLOR").is_some() {
+        return false;
+    }
+    if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+        return true;
+    }
+    ARGS.output.is_none()
+        && io::stdout().is_terminal()
+        && var("CLICOLOR").is_none_or(|value| value != "0")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Minimum number of hex address digits used when the input size is unknown
+const DEFAULT_ADDR_WIDTH: usize = 8;
+
+/// How long to wait before checking a followed input for new data
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to wait between checks for changes to a watched input
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often progress is recorded with --resume
+const RESUME_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Bytes read at a time for the picture of `visualize`
+const VISUALIZE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Files at least this big are memory mapped even without --mmap
+const MMAP_THRESHOLD: usize = 64 * 1024 * 1024;
+
+/// Whether regular files are read through io_uring rather than mapped automatically
+const USE_IO_URING: bool = cfg!(all(feature = "io-uring", target_os = "linux"));
+
+fn get_color(byte: u8) -> Option<Color> {
+    THEME.class(char_class(byte))
+}
+
+/// Class of the character a byte stands for in --charset, anything past ASCII being high
+fn char_class(byte: u8) -> ByteClass {
+    match ARGS.charset.decode(byte) {
+        Some(c) if c.is_ascii() => ByteClass::of(c as u8),
+        _ => ByteClass::High,
+    }
+}
+
+/// The theme picked by --theme, or else by the config file
+fn load_theme() -> io::Result<Theme> {
+    let name = ARGS.theme.as_ref().or(CONFIG.theme.as_ref());
+    CONFIG
+        .theme(
+            name.map_or("default", String::as_str),
+            ARGS.color_map.as_ref().map_or(&[], |map| &map.0),
+        )
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+/// `text` in `color`, if there is one
+fn paint(text: String, color: Option<Color>) -> String {
+    match color {
+        Some(color) => text.color(color).to_string(),
+        None => text,
+    }
+}
+
+/// `text` of a byte in the colors of `paint`
+fn paint_byte(text: String, paint: Paint) -> String {
+    let text = text.normal();
+    let text = match paint.fg {
+        Some(color) => text.color(color),
+        None => text,
+    };
+    match paint.bg {
+        Some(color) => text.on_color(color).to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// `text` in the color of addresses and separators, if `use_color`
+fn dim(text: String, use_color: bool) -> String {
+    match use_color {
+        true => paint(text, THEME.dim),
+        false => text,
     }
 }
 
+/// Bytes around every byte whose entropy picks its color with --color-by entropy
+const ENTROPY_WINDOW: usize = 32;
+
+/// Color of a byte among `around`, from blue where they repeat to red where they look random
+fn heat_color(around: &[u8]) -> Color {
+    const HEAT: [Color; 5] = [
+        Color::Blue,
+        Color::Cyan,
+        Color::Green,
+        Color::Yellow,
+        Color::Red,
+    ];
+    let share = entropy::shannon(around) / entropy::max_shannon(ENTROPY_WINDOW);
+    HEAT[((share * HEAT.len() as f64) as usize).min(HEAT.len() - 1)]
+}
+
+/// Color of a byte with --color-by value, its hue turning from blue to red as it grows
+fn value_color(byte: u8) -> Color {
+    // Four of the six sides of the color wheel, from red back through green to blue
+    let hue = (255 - byte) as f64 / 255.0 * 4.0;
+    let side = (hue as usize).min(3);
+    let rising = ((hue - side as f64) * 255.0).round() as u8;
+    let falling = 255 - rising;
+    let (r, g, b) = match side {
+        0 => (255, rising, 0),
+        1 => (falling, 255, 0),
+        2 => (0, 255, rising),
+        _ => (0, falling, 255),
+    };
+    Color::TrueColor { r, g, b }
+}
+
 fn get_ascii(byte: u8) -> String {
+    match ARGS.charset.decode(byte) {
+        Some(c) => char_glyph(c),
+        None => CONFIG.glyphs.unknown.unwrap_or('×').to_string(),
+    }
+}
+
+/// Glyph of a character of the text column
+fn char_glyph(c: char) -> String {
+    match c {
+        c if c.is_ascii() => ascii_glyph(c as u8),
+        c if c.is_control() || charset::is_format(c) => "▴".to_string(),
+        // On a dotted circle, so that it takes a column like every other character
+        c if charset::is_combining(c) => format!("◌{c}"),
+        c => c.to_string(),
+    }
+}
+
+/// Glyph of an ASCII character, with one for each kind of whitespace and control character
+fn ascii_glyph(byte: u8) -> String {
+    if let Some(glyph) = CONFIG.glyphs.of(byte) {
+        return glyph.to_string();
+    }
     match byte {
         0 => "•".to_string(),
         9 => "⇥".to_string(),
         10 => "␊".to_string(),
         13 => "␍".to_string(),
         32 => "␣".to_string(),
-        32..=126 => (byte as char).to_string(),
-        128..=255 => "×".to_string(),
+        33..=126 => (byte as char).to_string(),
         _ => "▴".to_string(),
     }
 }
 
 fn addr_line(addr: usize, trailing_zeroes: usize, use_color: bool) -> String {
-    match ARGS.uppercase {
-        true => format!("0x{:0width$X}", addr, width = trailing_zeroes),
-        false => format!("0x{:0width$x}", addr, width = trailing_zeroes),
+    let mut line = ARGS
+        .addr_radix
+        .address(addr, trailing_zeroes, ARGS.uppercase);
+    if ARGS.addr_both {
+        // Padded after the parenthesis to the longest decimal of as many digits
+        let width = Radix::Decimal.digits(ARGS.addr_radix.largest(trailing_zeroes)) + 2;
+        line += &format!(" {:<width$}", format!("({addr})"));
     }
-    .apply_if(use_color, |x| x.color(Color::BrightBlack).to_string())
+    line.apply_if(use_color, |x| dim(x, true))
 }
 
-fn hex_line(buff: &[u8], bytes_read: usize, use_color: bool) -> String {
-    hex::encode(buff)
-        .as_bytes()
-        .chunks(2)
-        .take(bytes_read)
-        .map(std::str::from_utf8)
-        .enumerate()
-        .map(|(index, hex)| {
-            hex.unwrap()
-                .to_string()
-                .apply_if(ARGS.uppercase, |hex_string| hex_string.to_uppercase())
-                .apply_if(use_color, |hex_string| {
-                    hex_string.color(get_color(buff[index])).to_string()
-                })
-        })
-        .collect::<Vec<_>>()
-        .join(" ")
+fn timestamp_line(elapsed: Duration, use_color: bool) -> String {
+    format!("[{:>10.3}]", elapsed.as_secs_f64()).apply_if(use_color, |x| dim(x, true))
 }
 
-fn ascii_line(buff: &[u8], bytes_read: usize, use_color: bool) -> String {
-    buff.into_iter()
-        .take(bytes_read)
-        .map(|&byte| {
-            get_ascii(byte).apply_if(use_color, |byte_string| {
-                byte_string.color(get_color(byte)).to_string()
-            })
-        })
-        .collect()
+/// Marker of the bytes --sample leaves out between two lines
+fn skip_line(len: usize, use_color: bool) -> String {
+    format!("skipped: {len} bytes").apply_if(use_color, |x| dim(x, true))
 }
 
-fn read_binary_file() -> io::Result<()> {
-    let ifile = File::open(ARGS.input.clone())?;
-    let ifile_size = ifile.metadata()?.len();
+/// Number of bytes a line of `hole_line` stands for and their value, if `text` is one
+fn collapsed_bytes(text: &str) -> Option<(usize, u8)> {
+    match text.strip_prefix("hole: ") {
+        Some(rest) => Some((rest.strip_suffix(" bytes of zeros")?.parse().ok()?, 0)),
+        None => {
+            let rest = text
+                .strip_prefix("gap: ")?
+                .strip_suffix(" not covered by any record")?;
+            let (len, fill) = rest.split_once(" bytes of 0x")?;
+            Some((len.parse().ok()?, u8::from_str_radix(fill, 16).ok()?))
+        }
+    }
+}
 
-    let trailing_zeroes = (ifile_size as f64).log(16.0).ceil() as usize;
+fn hole_line(len: usize, is_gap: bool, use_color: bool) -> String {
+    match is_gap {
+        true => format!("gap: {len} bytes of {GAP_FILL:#04x} not covered by any record"),
+        false => format!("hole: {len} bytes of zeros"),
+    }
+    .apply_if(use_color, |x| dim(x, true))
+}
 
-    let mut reader = BufReader::new(ifile);
-    reader.seek(io::SeekFrom::Start(*START as u64))?;
+/// Joins the cells of the hex column, one for each group of bytes starting at an offset
+/// within the line, with a split wherever --split-every falls between two groups
+fn hex_columns(cell: impl Fn(usize) -> String) -> String {
+    let mut line = String::new();
+    for start in (0..*BYTES_PER_LINE).step_by(*GROUP) {
+        if start > 0 {
+            match *SPLIT_EVERY {
+                Some(every) if (start - *GROUP) / every != start / every => {
+                    line += &format!(" {} ", *SPLIT_SYMBOL)
+                }
+                _ => line += " ",
+            }
+        }
+        line += &cell(start);
+    }
+    line
+}
 
-    let buffer_size = ARGS.bytes_per_line;
-    let mut buffer = vec![0u8; buffer_size];
+/// Number of splits --split-every puts in the hex column of `count` bytes
+fn hex_split_count(count: usize) -> usize {
+    match *SPLIT_EVERY {
+        Some(every) => (0..count)
+            .step_by(*GROUP)
+            .skip(1)
+            .filter(|start| (start - *GROUP) / every != start / every)
+            .count(),
+        None => 0,
+    }
+}
 
-    let mut current_addr = *START;
-    let mut total_bytes_read = 0;
+/// Width in characters of a line of `count` bytes in the selected style
+fn line_width(count: usize) -> usize {
+    let digits = RADIX.byte_digits(ARGS.signed);
+    match *STYLE {
+        Style::HexThing => {
+            let timestamp = match ARGS.serial {
+                Some(_) => timestamp_line(Duration::ZERO, false).len() + 1,
+                None => 0,
+            };
+            // Inputs of unknown size get the default width, and so does this guess
+            let addr = match ARGS.no_addr {
+                true => 0,
+                false => addr_line(0, addr_width(None, ARGS.addr_radix), false).len() + 3,
+            };
+            let groups = count.div_ceil(*GROUP);
+            let cells = match ARGS.little_endian {
+                true => groups * *GROUP,
+                false => count,
+            } * digits;
+            let hex = cells + groups.saturating_sub(1) + 2 * hex_split_count(count);
+            // A column of no bytes is only the padding of a full one
+            let words = words_line(&[], count).chars().count();
+            let ascii = match ARGS.no_ascii || ARGS.ascii_only {
+                true => 0,
+                // The bar has a space on either side, like the line itself at its start
+                false => 3 + ascii_width(count),
+            };
+            let entropy = match ARGS.entropy {
+                true => 3 + 6,
+                false => 0,
+            };
+            let density = match ARGS.density {
+                true => 3 + count.div_ceil(2),
+                false => 0,
+            };
+            let checksum = ARGS
+                .line_checksum
+                .map_or(0, |checksum| 3 + checksum.digits());
+            // The sides of --border have a space before the one on the right
+            let border = match ARGS.border {
+                true => 3,
+                false => 0,
+            };
+            1 + timestamp + addr + hex + words + ascii + entropy + density + checksum + border
+        }
+        Style::Xxd => 10 + count * 2 + count.div_ceil(2).saturating_sub(1) + 2 + count,
+        Style::Hexdump => 10 + count * 3 + 1 + 1 + count + 2,
+        Style::Od => 6 + count * 3 + 2 + count + 2,
+        Style::Plain => count * 2,
+    }
+}
 
-    let mut writer = match ARGS.output.clone() {
-        Some(of_name) => Some(BufWriter::new(File::create_new(of_name)?)),
-        None => None,
+/// The most bytes, in whole groups, that fit a line into the terminal
+fn fitting_line_length() -> usize {
+    let Some(width) = terminal::width() else {
+        return DEFAULT_BYTES_PER_LINE;
     };
+    let step = match *STYLE {
+        Style::HexThing => *GROUP,
+        _ => 1,
+    };
+    let mut count = step;
+    while line_width(count + step) <= width {
+        count += step;
+    }
+    count
+}
 
-    loop {
-        let bytes_read = reader.read(&mut buffer)?;
+/// Stands in for the digits of the bytes missing from a short line
+const PLACEHOLDER: char = '·';
 
-        if bytes_read == 0 {
-            // End of file
-            break;
-        }
+/// Dimmed placeholders for `len` columns of a line
+fn placeholder(len: usize) -> String {
+    match len {
+        0 => String::new(),
+        len => dim(PLACEHOLDER.to_string().repeat(len), *USE_COLOR),
+    }
+}
 
-        if MAX_COUNT.is_some() && total_bytes_read >= MAX_COUNT.unwrap() {
-            // Out of range
-            break;
+/// The hex column of a line, padded to the width of a full one
+fn hex_line(buff: &[u8], bytes_read: usize, colors: &[Paint]) -> String {
+    let digits = RADIX.byte_digits(ARGS.signed);
+    let byte_digits = |index: usize| {
+        let digits = RADIX.byte(buff[index], ARGS.uppercase, ARGS.signed);
+        paint_byte(digits, colors.get(index).copied().unwrap_or_default())
+    };
+    hex_columns(|start| {
+        let end = (start + *GROUP).min(*BYTES_PER_LINE);
+        let group = start.min(bytes_read)..end.min(bytes_read);
+        match ARGS.little_endian {
+            // A partial word is aligned to the right, where its least significant byte goes
+            true => {
+                placeholder((*GROUP - group.len()) * digits)
+                    + &group.rev().map(byte_digits).collect::<String>()
+            }
+            false => {
+                let padding = placeholder((end - start - group.len()) * digits);
+                group.map(byte_digits).collect::<String>() + &padding
+            }
         }
+    })
+}
 
-        let bytes_read =
-            if MAX_COUNT.is_some() && total_bytes_read + bytes_read >= MAX_COUNT.unwrap() {
-                MAX_COUNT.unwrap() - total_bytes_read
-            } else {
-                bytes_read
-            };
-
-        let addr = addr_line(current_addr, trailing_zeroes, *USE_COLOR);
-        let hex = hex_line(&buffer, bytes_read, *USE_COLOR);
-        let ascii = ascii_line(&buffer, bytes_read, *USE_COLOR);
-
-        let extra_space = " ".repeat((ARGS.bytes_per_line - bytes_read) * 3);
+/// The text column of --ascii-only, each glyph in a cell as wide as the digits of a byte
+fn glyph_line(glyphs: &[String], colors: &[Paint]) -> String {
+    let padding = " ".repeat(RADIX.byte_digits(ARGS.signed) - 1);
+    hex_columns(|start| {
+        let end = (start + *GROUP).min(*BYTES_PER_LINE);
+        (start..end)
+            .map(|index| match glyphs.get(index) {
+                Some(glyph) => {
+                    padding.clone()
+                        + &paint_byte(
+                            glyph.clone(),
+                            colors.get(index).copied().unwrap_or_default(),
+                        )
+                }
+                None => placeholder(padding.len() + 1),
+            })
+            .collect()
+    })
+}
 
-        let output = format!(
-            " {} {} {}{} {} {}\n",
-            addr, *SPLIT_SYMBOL, hex, extra_space, *SPLIT_SYMBOL, ascii
-        );
-        match &mut writer {
-            Some(w) => w.write_all(output.as_bytes())?,
-            None => {
-                print!("{output}");
+/// The text column of a line, which is only padded to the width of a full one if `padded`,
+/// for something after it
+fn ascii_line(glyphs: &[String], colors: &[Paint], padded: bool) -> String {
+    let count = match padded {
+        true => *BYTES_PER_LINE,
+        false => glyphs.len(),
+    };
+    (0..count)
+        .map(|index| {
+            let glyph = match glyphs.get(index) {
+                Some(glyph) => paint_byte(
+                    glyph.clone(),
+                    colors.get(index).copied().unwrap_or_default(),
+                ),
+                None => " ".to_string(),
+            };
+            match *SPLIT_EVERY {
+                Some(every) if index > 0 && index % every == 0 => {
+                    format!("{}{glyph}", *SPLIT_SYMBOL)
+                }
+                _ => glyph,
             }
-        };
+        })
+        .collect()
+}
 
-        current_addr += bytes_read;
-        total_bytes_read += bytes_read;
+/// Columns of --words and --floats for `bytes`, as wide as for `bytes_per_line` bytes,
+/// each following a split
+fn words_line(bytes: &[u8], bytes_per_line: usize) -> String {
+    let mut words = String::new();
+    if let Some(word) = ARGS.words {
+        let column = words::column(bytes, bytes_per_line, word, ARGS.endian, ARGS.signed);
+        words += &format!(" {} {}", *SPLIT_SYMBOL, column);
     }
-
-    if let Some(w) = &mut writer {
-        w.flush()?;
+    if let Some(float) = ARGS.floats {
+        let column = words::float_column(bytes, bytes_per_line, float, ARGS.endian);
+        words += &format!(" {} {}", *SPLIT_SYMBOL, column);
     }
+    words
+}
 
-    Ok(())
+/// Puts the columns of a line together, leaving out the text column for --no-ascii,
+/// and for --ascii-only, whose glyphs take the place of the hex column
+fn join_line(prefix: String, hex: String, words: String, ascii: String, tail: String) -> String {
+    let line = match ARGS.no_ascii || ARGS.ascii_only {
+        // Without the text column the padding of the last line is left trailing,
+        // unless a column comes after it
+        true if !tail.is_empty() => format!(" {}{}{}", prefix, hex, words),
+        true => format!(" {}{}{}", prefix, hex, words)
+            .trim_end()
+            .to_string(),
+        false => format!(" {}{}{} {} {}", prefix, hex, words, *SPLIT_SYMBOL, ascii),
+    };
+    line + &tail
 }
 
-fn reverse_operation() -> io::Result<()> {
-    let ifile = File::open(ARGS.input.clone())?;
-    let reader = BufReader::new(ifile);
+/// Width in characters of the text column of `count` bytes
+fn ascii_width(count: usize) -> usize {
+    count + SPLIT_EVERY.map_or(0, |every| count.saturating_sub(1) / every)
+}
 
-    let mut out_hex: Vec<u8> = Vec::new();
+/// Bars of --entropy, from none at all to the most a full line can have
+const ENTROPY_BARS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 
-    for (index, line) in reader.lines().enumerate() {
-        let line = line?;
-        let parts: Vec<&str> = line.trim().split(*RAW_SPLIT_SYMBOL).collect();
+/// Column of --entropy with the entropy of `bytes` and a bar of it, relative to the most
+/// a full line can have. Rulers have no bytes and get a blank column
+fn entropy_line(bytes: Option<&[u8]>, use_color: bool) -> String {
+    if !ARGS.entropy {
+        return String::new();
+    }
+    let Some(bytes) = bytes else {
+        return format!(" {} {:6}", *SPLIT_SYMBOL, "");
+    };
+    let bits = entropy::shannon(bytes);
+    let share = (bits / entropy::max_shannon(*BYTES_PER_LINE)).min(1.0);
+    let bar = ENTROPY_BARS[(share * (ENTROPY_BARS.len() - 1) as f64).round() as usize];
+    // Packed or encrypted bytes stand out in red
+    let color = match share {
+        share if share < 0.5 => Color::BrightBlack,
+        share if share < 0.85 => Color::Yellow,
+        _ => Color::Red,
+    };
+    let column = format!("{bits:.2} {bar}").apply_if(use_color, |x| x.color(color).to_string());
+    format!(" {} {}", *SPLIT_SYMBOL, column)
+}
 
-        let hex_str = match parts.len() {
-            1 => parts[0],
-            2 | 3 => parts[1],
-            _ => {
-                eprintln!(
-                    "Error: Unrecognized input format for reverse operation on line {}",
-                    index + 1
-                );
-                std::process::exit(1);
-            }
-        };
-        let hex_str = hex_str.replace(" ", "");
+/// Header with the offset of every column within a line, lined up with the lines below it
+fn ruler_line(addr_width: usize, use_color: bool) -> String {
+    let timestamp = match ARGS.serial {
+        Some(_) => " ".repeat(timestamp_line(Duration::ZERO, false).len() + 1),
+        None => String::new(),
+    };
+    let addr = match ARGS.no_addr {
+        true => String::new(),
+        false => format!(
+            "{} {} ",
+            " ".repeat(addr_line(0, addr_width, false).len()),
+            *SPLIT_SYMBOL
+        ),
+    };
 
-        let mut hex = match hex::decode(hex_str.clone()) {
-            Ok(bin) => bin,
-            Err(_) => {
-                eprintln!(
-                    "Error: Unable to decode hex \"{}\" on line {} ",
-                    hex_str,
-                    index + 1
-                );
-                std::process::exit(1);
-            }
+    let hex = hex_columns(|start| {
+        // Partial words of -e are padded to full ones
+        let width = match ARGS.little_endian {
+            true => *GROUP,
+            false => (*BYTES_PER_LINE - start).min(*GROUP),
+        } * RADIX.byte_digits(ARGS.signed);
+        let label = match ARGS.uppercase {
+            true => format!("{start:02X}"),
+            false => format!("{start:02x}"),
         };
+        // Offsets too long for their column keep their last digits
+        let label = &label[label.len().saturating_sub(width)..];
+        format!("{label:<width$}")
+    });
+    let ascii: String = (0..*BYTES_PER_LINE)
+        .map(|offset| {
+            let digit = char::from_digit((offset % 16) as u32, 16).expect("offset is below 16");
+            let digit = match ARGS.uppercase {
+                true => digit.to_ascii_uppercase(),
+                false => digit,
+            };
+            match *SPLIT_EVERY {
+                Some(every) if offset > 0 && offset % every == 0 => {
+                    format!("{}{digit}", *RAW_SPLIT_SYMBOL)
+                }
+                _ => digit.to_string(),
+            }
+        })
+        .collect();
+    join_line(
+        timestamp + &addr,
+        dim(hex, use_color),
+        words_line(&[], *BYTES_PER_LINE),
+        dim(ascii, use_color),
+        entropy_line(None, use_color) + &density_line(None) + &checksum_line(None, use_color),
+    )
+}
 
-        out_hex.append(&mut hex);
+/// Footer of --summary, with how many bytes were shown out of how many
+fn summary_line(
+    shown: usize,
+    start: usize,
+    end: usize,
+    size: Option<usize>,
+    use_color: bool,
+) -> String {
+    let range = match ARGS.uppercase {
+        true => format!("0x{start:X}-0x{end:X}"),
+        false => format!("0x{start:x}-0x{end:x}"),
+    };
+    match size {
+        Some(size) => format!("{shown} of {size} bytes shown, {range}"),
+        None => format!("{shown} bytes shown, {range}"),
     }
+    .apply_if(use_color, |x| dim(x, true))
+}
 
-    let ofile = File::create(ARGS.output.clone().expect("No output argument found"))?;
-    let mut writer = BufWriter::new(ofile);
+/// Footer of --stats, with how many of the bytes shown are of each class
+fn stats_line(counts: &ClassCounts, use_color: bool) -> String {
+    let total = counts.total().max(1);
+    ByteClass::ALL
+        .iter()
+        .map(|&class| {
+            let count = counts.count(class);
+            let percent = count as f64 * 100.0 / total as f64;
+            let name = class
+                .name()
+                .to_string()
+                .apply_if(use_color, |x| paint(x, THEME.class(class)));
+            format!("{name} {count} ({percent:.1}%)")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-    writer.write_all(&out_hex)?;
-    writer.flush()?;
+/// Column of --density, each braille character holding two bars as high as the values of
+/// two bytes
+fn density_line(bytes: Option<&[u8]>) -> String {
+    if !ARGS.density {
+        return String::new();
+    }
+    // Dots of the left and of the right bar of a braille character, from the bottom up
+    const DOTS: [[u32; 4]; 2] = [[0x40, 0x04, 0x02, 0x01], [0x80, 0x20, 0x10, 0x08]];
+    let sparkline: String = bytes
+        .unwrap_or_default()
+        .chunks(2)
+        .map(|pair| {
+            let dots = pair.iter().zip(DOTS).fold(0, |dots, (&byte, bar)| {
+                let height = (byte as usize).div_ceil(64);
+                dots | bar[..height].iter().sum::<u32>()
+            });
+            char::from_u32(0x2800 + dots).unwrap_or(' ')
+        })
+        .collect();
+    let width = BYTES_PER_LINE.div_ceil(2);
+    format!(" {} {sparkline:width$}", *SPLIT_SYMBOL)
+}
 
-    Ok(())
+/// Column of --line-checksum with the checksum of `bytes`, blank for rulers
+fn checksum_line(bytes: Option<&[u8]>, use_color: bool) -> String {
+    let Some(checksum) = ARGS.line_checksum else {
+        return String::new();
+    };
+    let digits = checksum.digits();
+    let column = match bytes {
+        Some(bytes) => match ARGS.uppercase {
+            true => format!("{:0digits$X}", checksum.of(bytes)),
+            false => format!("{:0digits$x}", checksum.of(bytes)),
+        },
+        None => " ".repeat(digits),
+    };
+    format!(
+        " {} {}",
+        *SPLIT_SYMBOL,
+        column.apply_if(use_color, |x| dim(x, true))
+    )
 }
 
-fn main() {
-    if let Err(e) = match ARGS.reverse {
-        true => reverse_operation(),
-        false => read_binary_file(),
-    } {
-        eprintln!("{e}");
-        std::process::exit(1);
+fn header_line(path: &Path, use_color: bool) -> String {
+    format!("{}:", path.display()).apply_if(use_color, |x| x.bold().color(Color::Blue).to_string())
+}
+
+/// Paths of every input to process, from the command line, the --files-from list,
+/// or the --recursive directory
+fn input_paths() -> io::Result<Vec<PathBuf>> {
+    if let Some(dir) = &ARGS.recursive {
+        return walk::files(dir, &ARGS.glob);
+    }
+    match &ARGS.files_from {
+        Some(list) => input::read_path_list(list, ARGS.null),
+        None => Ok(ARGS.input.clone()),
+    }
+}
+
+fn dump_files(paths: &[PathBuf]) -> io::Result<()> {
+    let format = ARGS
+        .format
+        .to_possible_value()
+        .expect("no format is hidden");
+    if paths.len() > 1 && ARGS.format.is_single() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "--format {} only supports a single input",
+                format.get_name()
+            ),
+        ));
+    }
+    let resumed = match &ARGS.resume {
+        Some(_) if paths.len() > 1 => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--resume only supports a single input",
+            ))
+        }
+        Some(_) if !ARGS.format.streams() => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "--resume can't continue --format {}, which is only complete once the \
+                     whole input is read",
+                    format.get_name()
+                ),
+            ));
+        }
+        Some(state) => resume::load(state)?,
+        None => None,
+    };
+
+    let mut writer: Box<dyn Write> = match (ARGS.output.clone(), resumed) {
+        (Some(of_name), Some(progress)) => {
+            Box::new(BufWriter::new(resume::reopen_output(&of_name, progress)?))
+        }
+        (Some(of_name), None) => Box::new(BufWriter::new(File::create_new(of_name)?)),
+        // Dumps of streams that go on until they are stopped are kept out of the pager
+        (None, _)
+            if !ARGS.no_pager
+                && !ARGS.follow
+                && !ARGS.watch
+                && ARGS.listen.is_none()
+                && ARGS.serial.is_none()
+                && ARGS.connect.is_none()
+                && io::stdout().is_terminal() =>
+        {
+            match Pager::new() {
+                Some(pager) => Box::new(pager),
+                None => Box::new(io::stdout().lock()),
+            }
+        }
+        (None, _) => Box::new(io::stdout().lock()),
+    };
+    if let Some(lines) = ARGS.lines {
+        writer = Box::new(LineLimit::new(writer, lines));
+    }
+
+    if let Some(addr) = &ARGS.listen {
+        let input = Input::Stream(socket::listen(addr)?, None);
+        dump_input(input, None, &mut writer, ReadMode::Live)?;
+        return writer.flush();
+    }
+    if let Some(pid) = ARGS.pid {
+        let input = Input::Memory(ProcessMemory::open(pid)?);
+        dump_input(input, None, &mut writer, ReadMode::Full)?;
+        return writer.flush();
+    }
+    if let Some(device) = &ARGS.serial {
+        let input = Input::File(serial::open(device, ARGS.baud)?);
+        dump_input(input, None, &mut writer, ReadMode::Live)?;
+        return writer.flush();
+    }
+    if let Some(addr) = &ARGS.connect {
+        let input = Input::Stream(socket::connect(addr)?, None);
+        dump_input(input, None, &mut writer, ReadMode::Live)?;
+        return writer.flush();
+    }
+
+    // Headers would break the syntax of code and structured formats
+    let show_headers =
+        *SEVERAL_INPUTS && !ARGS.no_header && LANG.is_none() && ARGS.format == Format::Text;
+
+    let last_index = paths.len().saturating_sub(1);
+    // The documents of several inputs go in an array, so the output stays a single one
+    let json_array = *SEVERAL_INPUTS && ARGS.format == Format::Json;
+    if json_array {
+        write!(writer, "[")?;
+    }
+
+    for (index, path) in paths.iter().enumerate() {
+        if json_array {
+            let separator = match index {
+                0 => "",
+                _ => ",",
+            };
+            writeln!(writer, "{separator}")?;
+        }
+        if show_headers {
+            if index > 0 {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "{}", header_line(path, *USE_COLOR))?;
+        }
+        let mode = match ARGS.follow && index == last_index {
+            true => ReadMode::Follow,
+            false => ReadMode::Full,
+        };
+        read_binary_file(path, &mut writer, mode)?;
+    }
+    if json_array {
+        writeln!(writer, "\n]")?;
+    }
+
+    writer.flush()
+}
+
+/// Skips over up to `len` bytes of `reader` from `addr`, seeking (or moving on in `map`)
+/// rather than reading where the input allows it, and returns how many there were
+fn skip_over(
+    reader: &mut BufReader<Input>,
+    map: Option<&Mmap>,
+    seek_end: Option<usize>,
+    addr: usize,
+    len: usize,
+) -> io::Result<usize> {
+    match (map, seek_end) {
+        (Some(map), _) => Ok(len.min(map.len().saturating_sub(addr))),
+        (None, Some(end)) => {
+            let skipped = len.min(end.saturating_sub(addr));
+            reader.seek_relative(skipped as i64)?;
+            Ok(skipped)
+        }
+        (None, None) => io::copy(&mut reader.take(len as u64), &mut io::sink()).map(|n| n as usize),
+    }
+}
+
+/// Fills `buf` from `reader`, waiting for more data to be appended whenever it hits EOF.
+/// Like `tail -f`, what was read is returned as it is once a wait brings nothing more
+fn read_following<R: Read>(
+    reader: &mut R,
+    buf: &mut [u8],
+    writer: &mut impl Write,
+) -> io::Result<usize> {
+    let mut filled = read_full(reader, buf)?;
+    while filled < buf.len() {
+        writer.flush()?;
+        thread::sleep(FOLLOW_POLL_INTERVAL);
+        match read_full(reader, &mut buf[filled..])? {
+            0 if filled > 0 => break,
+            read => filled += read,
+        }
+    }
+    Ok(filled)
+}
+
+/// How each line's worth of bytes is gathered from the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadMode {
+    /// Fill every line, stopping at EOF
+    Full,
+    /// Fill every line, waiting for more data at EOF
+    Follow,
+    /// Show whatever a single read returns, for interactive streams like sockets
+    Live,
+}
+
+fn read_binary_file(path: &Path, writer: &mut impl Write, mode: ReadMode) -> io::Result<()> {
+    let mut input = Input::open(path)?;
+    let detect = matches!(input, Input::File(_) | Input::Stdin(_))
+        && mode == ReadMode::Full
+        && !ARGS.no_decompress;
+    if ARGS.decompress || detect {
+        input = input.decompressed()?;
+    }
+    if let Some(name) = &ARGS.archive_member {
+        input = input.archive_member(name)?;
+    }
+    input = input.decoded(ARGS.input_format)?;
+    dump_input(input, Some(path), writer, mode)
+}
+
+/// Top or bottom of the frame of --border, joining the separators of the lines within it
+fn border_line(addr_width: usize, left: char, tee: char, right: char) -> String {
+    let layout = strip_ansi(&ruler_line(addr_width, false));
+    let rule = match RAW_SPLIT_SYMBOL.is_empty() {
+        true => "─".repeat(layout.chars().count()),
+        false => layout
+            .split(*RAW_SPLIT_SYMBOL)
+            .map(|column| "─".repeat(column.chars().count()))
+            .collect::<Vec<_>>()
+            .join(&tee.to_string().repeat(RAW_SPLIT_SYMBOL.chars().count())),
+    };
+    format!("{left}{rule}─{right}").apply_if(*USE_COLOR, |x| dim(x, true))
+}
+
+/// What the header of a region of --annotate starts with
+const SECTION_RULE: &str = "──";
+
+/// What comes before the label of a --mark, after the line it starts on
+const LABEL_PREFIX: &str = "  ◂ ";
+
+/// What --paginate starts every page after the first with
+const FORM_FEED: char = '\x0c';
+
+/// The default style
+struct HexThing {
+    /// Number of address digits, which grows along with streams of unknown size
+    addr_width: usize,
+    grow_addr: bool,
+    started: Instant,
+    /// Number of lines written, to know when to repeat the ruler
+    lines: usize,
+    /// Repeated lines of --autoskip and --squeeze-count
+    squeeze: Option<Squeeze>,
+    /// Address of the last line left out as a repeat, and how its text was decoded
+    skipped: Option<(usize, Vec<TextByte>)>,
+    /// Number of lines left out in the current run of repeats
+    repeated: usize,
+    /// End of the last line, whose bytes are around the first ones of the next
+    recent: Vec<u8>,
+    /// Whether each --mark was labeled yet
+    labeled: Vec<bool>,
+    /// How the first bytes of the next line were decoded, along with a character of more than
+    /// a byte that the last line ended in the middle of
+    carry: Vec<TextByte>,
+    /// Lines held back until the bytes after them finish the character they end in
+    ahead: VecDeque<(usize, Vec<u8>)>,
+    /// Regions of --annotate, and how many of them the dump got past
+    regions: Vec<Region>,
+    next_region: usize,
+}
+
+impl HexThing {
+    /// Colors of every byte of a line at `addr`, as picked by --color-by, then --highlight
+    /// and then --mark
+    fn colors(&mut self, addr: usize, bytes: &[u8]) -> Vec<Paint> {
+        let colors: Vec<_> = match ARGS.color_by {
+            ColorBy::Class => bytes.iter().map(|&byte| get_color(byte)).collect(),
+            ColorBy::Value => bytes.iter().map(|&byte| Some(value_color(byte))).collect(),
+            ColorBy::Entropy => {
+                let half = ENTROPY_WINDOW / 2;
+                let window = [self.recent.as_slice(), bytes].concat();
+                let colors = (self.recent.len()..window.len())
+                    .map(|center| {
+                        let around = center.saturating_sub(half)..(center + half).min(window.len());
+                        Some(heat_color(&window[around]))
+                    })
+                    .collect();
+                self.recent = window[window.len().saturating_sub(half)..].to_vec();
+                colors
+            }
+        };
+        colors
+            .into_iter()
+            .zip(bytes)
+            .enumerate()
+            .map(|(index, (color, &byte))| {
+                let paint = Paint::fg(color);
+                let paint = match ARGS
+                    .highlight
+                    .as_ref()
+                    .and_then(|highlights| highlights.0[byte as usize])
+                {
+                    Some(highlight) => paint.over(highlight),
+                    None => paint,
+                };
+                ARGS.mark
+                    .iter()
+                    .filter(|mark| mark.range.contains(&(addr + index)))
+                    .fold(paint, |paint, mark| paint.over(mark.paint))
+            })
+            .collect()
+    }
+
+    /// Labels of the marks first seen in the `len` bytes at `addr`, to follow their line
+    fn labels(&mut self, addr: usize, len: usize) -> String {
+        let mut labels = String::new();
+        for (mark, labeled) in ARGS.mark.iter().zip(&mut self.labeled) {
+            let Some(label) = mark
+                .label
+                .as_ref()
+                .filter(|_| !*labeled && mark.overlaps(addr, len))
+            else {
+                continue;
+            };
+            *labeled = true;
+            labels += LABEL_PREFIX;
+            labels += &match *USE_COLOR {
+                true => paint_byte(label.clone(), mark.paint),
+                false => label.clone(),
+            };
+        }
+        labels
+    }
+
+    /// How the bytes of a line show in the text column, given the `next` ones after it and
+    /// whether `more` can follow those. None while it ends in a character that isn't finished
+    fn decode(&mut self, bytes: &[u8], next: &[u8], more: bool) -> Option<Vec<TextByte>> {
+        match ARGS.charset {
+            Charset::Utf8 => charset::decode_utf8(&mut self.carry, bytes, next, more),
+            Charset::Utf16le => charset::decode_utf16(&mut self.carry, bytes, next, more, false),
+            Charset::Utf16be => charset::decode_utf16(&mut self.carry, bytes, next, more, true),
+            Charset::ShiftJis | Charset::Gbk | Charset::Big5 => {
+                charset::decode_double(&mut self.carry, bytes, next, more, ARGS.charset)
+            }
+            _ => Some(bytes.iter().map(|&byte| TextByte::Byte(byte)).collect()),
+        }
+    }
+
+    /// Glyph of every byte of a line in the text column. In the charsets of more than a byte
+    /// a character takes the column of its first byte, dimming the `colors` of the others
+    fn glyphs(&mut self, decoded: &[TextByte], colors: &mut [Paint]) -> Vec<String> {
+        let mut glyphs = Vec::with_capacity(decoded.len());
+        let mut covered = false;
+        for (index, &byte) in decoded.iter().enumerate() {
+            let glyph = match byte {
+                TextByte::Byte(byte) => get_ascii(byte),
+                TextByte::Part if std::mem::take(&mut covered) => String::new(),
+                TextByte::Part => "·".to_string(),
+                // A wide character also takes the column of the next of its bytes, even past
+                // a split, and only sticks out of a line that ends before that
+                TextByte::Char(c) if charset::is_wide(c) => {
+                    covered = decoded.get(index + 1) == Some(&TextByte::Part);
+                    c.to_string()
+                }
+                TextByte::Char(c) => char_glyph(c),
+            };
+            if byte == TextByte::Part {
+                if let Some(paint) = colors.get_mut(index) {
+                    paint.fg = THEME.dim;
+                }
+            }
+            glyphs.push(glyph);
+        }
+        glyphs
+    }
+
+    /// Whether a region of --annotate starts in the `len` bytes at `addr`, or did before them
+    /// without a header yet
+    fn enters_region(&self, addr: usize, len: usize) -> bool {
+        self.regions
+            .get(self.next_region)
+            .is_some_and(|region| region.start < addr + len)
+    }
+
+    /// Writes the headers of the regions of --annotate starting in the `len` bytes at `addr`.
+    /// The dump starting inside a region gets its header as well
+    fn write_headers(&mut self, writer: &mut dyn Write, addr: usize, len: usize) -> io::Result<()> {
+        while self.enters_region(addr, len) {
+            let region = &self.regions[self.next_region];
+            self.next_region += 1;
+            // Regions over before the dump got to them, or followed by one it is already in
+            let next = self.regions.get(self.next_region);
+            let over = match region.end {
+                Some(end) => end < addr,
+                None => next.is_some_and(|next| next.start <= addr),
+            };
+            if over {
+                continue;
+            }
+            let width = strip_ansi(&ruler_line(self.addr_width, false))
+                .chars()
+                .count();
+            let title = format!(
+                "{} @ {}",
+                region.name,
+                ARGS.addr_radix.address(region.start, 0, ARGS.uppercase)
+            );
+            // A space on either side of the title, and one before the line like the others
+            let rule = "─".repeat(width.saturating_sub(charset::width(&title) + 5));
+            let header = format!(
+                " {} {title} {}",
+                dim(SECTION_RULE.to_string(), *USE_COLOR),
+                dim(rule, *USE_COLOR)
+            );
+            self.write_framed(writer, &header)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `line`, framed by the sides of --border
+    fn write_framed(&self, writer: &mut dyn Write, line: &str) -> io::Result<()> {
+        writeln!(writer, "{}", self.framed(line))
+    }
+
+    /// `line` between the sides of --border
+    fn framed(&self, line: &str) -> String {
+        if !ARGS.border {
+            return line.to_string();
+        }
+        let width = strip_ansi(&ruler_line(self.addr_width, false))
+            .chars()
+            .count();
+        let padding = " ".repeat(width.saturating_sub(charset::width(&strip_ansi(line))));
+        let side = "│".to_string().apply_if(*USE_COLOR, |x| dim(x, true));
+        format!("{side}{line}{padding} {side}")
+    }
+
+    /// Ends a run of repeats with its count for --squeeze-count. Otherwise a run that
+    /// ends the dump, or comes before a hole, shows its last line to tell where it ends
+    fn end_run(&mut self, writer: &mut dyn Write, is_last: bool) -> io::Result<()> {
+        let skipped = self.skipped.take();
+        let repeated = std::mem::take(&mut self.repeated);
+        match (skipped, ARGS.squeeze_count) {
+            (Some(_), true) => {
+                let count =
+                    format!("* (repeated {repeated} times)").apply_if(*USE_COLOR, |x| dim(x, true));
+                self.write_framed(writer, &count)
+            }
+            (Some((addr, decoded)), false) if is_last => {
+                let bytes = self
+                    .squeeze
+                    .as_ref()
+                    .and_then(Squeeze::previous)
+                    .expect("a line was skipped as a repeat of the previous one")
+                    .to_vec();
+                self.write_line(writer, addr, &bytes, &decoded)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes `text` in place of the `len` bytes at `addr`, after the address like a line
+    fn write_collapsed(
+        &mut self,
+        writer: &mut dyn Write,
+        addr: usize,
+        len: usize,
+        text: &str,
+    ) -> io::Result<()> {
+        self.write_headers(writer, addr, len)?;
+        let line = match ARGS.no_addr {
+            true => format!(" {}", text),
+            false => {
+                let addr = addr_line(addr, self.addr_width, *USE_COLOR);
+                format!(" {} {} {}", addr, *SPLIT_SYMBOL, text)
+            }
+        };
+        self.write_framed(writer, &line)
+    }
+
+    /// Writes the lines held back as far as the bytes after them are known, which is all of
+    /// them unless `more` can follow
+    fn catch_up(&mut self, writer: &mut dyn Write, more: bool) -> io::Result<()> {
+        while let Some((addr, bytes)) = self.ahead.pop_front() {
+            let next: Vec<u8> = self
+                .ahead
+                .iter()
+                .flat_map(|(_, bytes)| bytes)
+                .take(charset::MAX_LEN - 1)
+                .copied()
+                .collect();
+            let Some(decoded) = self.decode(&bytes, &next, more) else {
+                self.ahead.push_front((addr, bytes));
+                return Ok(());
+            };
+            self.put_line(writer, addr, &bytes, decoded)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a line, unless it repeats the one before for --autoskip
+    fn put_line(
+        &mut self,
+        writer: &mut dyn Write,
+        addr: usize,
+        bytes: &[u8],
+        decoded: Vec<TextByte>,
+    ) -> io::Result<()> {
+        // A header goes before a line that is written, even if it repeats the one before
+        let entering = self.enters_region(addr, bytes.len());
+        if let Some(squeeze) = &mut self.squeeze {
+            if squeeze.repeats(writer, bytes)? && !entering {
+                // The run is marked as it starts, unless its count is shown as it ends
+                if self.repeated == 0 && !ARGS.squeeze_count {
+                    self.write_framed(writer, "*")?;
+                }
+                self.skipped = Some((addr, decoded));
+                self.repeated += 1;
+                return Ok(());
+            }
+            self.end_run(writer, false)?;
+        }
+        self.write_line(writer, addr, bytes, &decoded)
+    }
+
+    fn write_line(
+        &mut self,
+        writer: &mut dyn Write,
+        addr: usize,
+        bytes: &[u8],
+        decoded: &[TextByte],
+    ) -> io::Result<()> {
+        if self.grow_addr {
+            self.addr_width = self
+                .addr_width
+                .max(ARGS.addr_radix.digits(addr + bytes.len() - 1));
+        }
+
+        // Serial captures are timed from when the device was opened
+        let timestamp = match ARGS.serial {
+            Some(_) => timestamp_line(self.started.elapsed(), *USE_COLOR) + " ",
+            None => String::new(),
+        };
+        let prefix = match ARGS.no_addr {
+            true => String::new(),
+            false => addr_line(addr, self.addr_width, *USE_COLOR) + " " + &SPLIT_SYMBOL + " ",
+        };
+        let mut colors = match *USE_COLOR {
+            true => self.colors(addr, bytes),
+            false => Vec::new(),
+        };
+        let hex = match ARGS.ascii_only {
+            true => String::new(),
+            false => hex_line(bytes, bytes.len(), &colors),
+        };
+        let glyphs = self.glyphs(decoded, &mut colors);
+        let hex = match ARGS.ascii_only {
+            true => glyph_line(&glyphs, &colors),
+            false => hex,
+        };
+        let tail = entropy_line(Some(bytes), *USE_COLOR)
+            + &density_line(Some(bytes))
+            + &checksum_line(Some(bytes), *USE_COLOR);
+        let ascii = ascii_line(&glyphs, &colors, ARGS.border || !tail.is_empty());
+
+        let words = words_line(bytes, *BYTES_PER_LINE);
+
+        let page_break = ARGS
+            .paginate
+            .is_some_and(|every| self.lines > 0 && self.lines.is_multiple_of(every as usize));
+        if page_break {
+            if ARGS.border {
+                writeln!(writer, "{}", border_line(self.addr_width, '└', '┴', '┘'))?;
+            }
+            write!(writer, "{FORM_FEED}")?;
+            if ARGS.border {
+                writeln!(writer, "{}", border_line(self.addr_width, '┌', '┬', '┐'))?;
+            }
+        }
+        if let Some(every) = ARGS.gap_every {
+            if self.lines > 0 && self.lines.is_multiple_of(every as usize) && !page_break {
+                self.write_framed(writer, "")?;
+            }
+        }
+        let ruler = match ARGS.ruler {
+            Some(every) => self.lines == 0 || (every > 0 && self.lines.is_multiple_of(every)),
+            None => ARGS.paginate.is_some() && self.lines == 0,
+        };
+        if ruler || page_break {
+            self.write_framed(writer, &ruler_line(self.addr_width, *USE_COLOR))?;
+        }
+        self.lines += 1;
+        self.write_headers(writer, addr, bytes.len())?;
+
+        let line = join_line(timestamp + &prefix, hex, words, ascii, tail);
+        let labels = self.labels(addr, bytes.len());
+        writeln!(writer, "{}{labels}", self.framed(&line))
+    }
+}
+
+impl Renderer for HexThing {
+    fn begin(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        match ARGS.border {
+            true => writeln!(writer, "{}", border_line(self.addr_width, '┌', '┬', '┐')),
+            false => Ok(()),
+        }
+    }
+
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        self.ahead.push_back((addr, bytes.to_vec()));
+        self.catch_up(writer, true)
+    }
+
+    fn collapses_holes(&self) -> bool {
+        true
+    }
+
+    fn hole(
+        &mut self,
+        writer: &mut dyn Write,
+        addr: usize,
+        len: usize,
+        is_gap: bool,
+    ) -> io::Result<()> {
+        self.catch_up(writer, false)?;
+        self.end_run(writer, true)?;
+        // The line after a hole never repeats one before it
+        if self.squeeze.is_some() {
+            self.squeeze = Some(Squeeze::unmarked());
+        }
+        // Nor does a character go on past it
+        self.carry.clear();
+
+        self.write_collapsed(writer, addr, len, &hole_line(len, is_gap, *USE_COLOR))
+    }
+
+    fn skip(&mut self, writer: &mut dyn Write, addr: usize, len: usize) -> io::Result<()> {
+        self.catch_up(writer, false)?;
+        self.end_run(writer, true)?;
+        if self.squeeze.is_some() {
+            self.squeeze = Some(Squeeze::unmarked());
+        }
+        self.carry.clear();
+        self.write_collapsed(writer, addr, len, &skip_line(len, *USE_COLOR))
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        self.catch_up(writer, false)?;
+        self.end_run(writer, true)?;
+        match ARGS.border {
+            true => writeln!(writer, "{}", border_line(self.addr_width, '└', '┴', '┘')),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Address shown for the byte at `addr` within the input
+fn shown_addr(addr: usize) -> usize {
+    match ARGS.relative {
+        true => addr.saturating_sub(*START),
+        false => addr,
+    }
+    .saturating_add(ARGS.display_offset)
+}
+
+/// Number of address digits in `radix` needed for the first `shown_size` bytes of an input,
+/// unless --addr-width sets it
+fn addr_width(shown_size: Option<usize>, radix: Radix) -> usize {
+    if let Some(width) = ARGS.addr_width {
+        return width as usize;
+    }
+    match shown_size {
+        Some(size) => radix.digits(size.saturating_sub(1)),
+        None => radix.digits((1 << (4 * DEFAULT_ADDR_WIDTH)) - 1),
+    }
+}
+
+/// Renderer for the selected style, given what is known about the input
+fn renderer(
+    path: Option<&Path>,
+    shown_size: Option<usize>,
+    started: Instant,
+) -> io::Result<Box<dyn Renderer>> {
+    let file = path
+        .filter(|path| path.as_os_str() != input::STDIN_PATH)
+        .map(|path| path.to_string_lossy().into_owned());
+
+    match ARGS.format {
+        Format::Text => {}
+        Format::Json => return Ok(Box::new(Json::new(file, *SEVERAL_INPUTS))),
+        Format::Jsonl => return Ok(Box::new(JsonLines::new(file))),
+        Format::Csv => return Ok(Box::new(Csv::new(',', ARGS.uppercase))),
+        Format::Tsv => return Ok(Box::new(Csv::new('\t', ARGS.uppercase))),
+        Format::Html => {
+            let title = file.unwrap_or_else(|| "standard input".to_string());
+            let width = addr_width(shown_size, Radix::Hex);
+            let html = Html::new(title, *BYTES_PER_LINE, width, ARGS.uppercase, get_ascii);
+            return Ok(Box::new(html));
+        }
+        Format::Svg => {
+            let width = addr_width(shown_size, Radix::Hex);
+            let svg = Svg::new(*BYTES_PER_LINE, width, ARGS.uppercase, get_ascii);
+            return Ok(Box::new(svg));
+        }
+        Format::Markdown => {
+            let markdown = Markdown::new(addr_width(shown_size, Radix::Hex), ARGS.uppercase);
+            return Ok(Box::new(markdown));
+        }
+        Format::Escaped => {
+            let escaped = Escaped::new(ARGS.sep.clone(), ARGS.uppercase);
+            return Ok(Box::new(escaped));
+        }
+        Format::Ihex => {
+            let ihex = Ihex::new(ARGS.record_size as usize, ARGS.load_address as u64);
+            return Ok(Box::new(ihex));
+        }
+        Format::Srec => {
+            let header = file.unwrap_or_default();
+            let srec = Srec::new(header, ARGS.record_size as usize, ARGS.load_address as u64);
+            return Ok(Box::new(srec));
+        }
+        Format::Uf2 => {
+            let family_id = ARGS.family_id.map(|id| id as u32);
+            let uf2 = Uf2::new(ARGS.load_address as u64, family_id);
+            return Ok(Box::new(uf2));
+        }
+    }
+
+    if let Some(template) = &ARGS.template {
+        let text = fs::read_to_string(template)?;
+        let template = Template::parse(&text, *BYTES_PER_LINE, ARGS.uppercase)?;
+        return Ok(Box::new(template));
+    }
+    if let Some(lang) = *LANG {
+        // Like xxd, standard input only gets the elements of an array without a name
+        let name = ARGS
+            .name
+            .clone()
+            .or_else(|| Some(code::constant_name(file.as_deref()?, lang)));
+        let code = Code::new(lang, name, ARGS.uppercase, ARGS.visibility, ARGS.slice);
+        return Ok(Box::new(code));
+    }
+
+    if let Some(block_size) = ARGS.overview {
+        let addr_width = addr_width(shown_size, ARGS.addr_radix);
+        // Rows are a multiple of 16 blocks wide, so their addresses stay round
+        let prefix = ARGS.addr_radix.address(0, addr_width, false).len() + 3;
+        let blocks_per_row = terminal::width().map_or(overview::DEFAULT_BLOCKS_PER_ROW, |width| {
+            (width.saturating_sub(prefix) / 16 * 16).max(16)
+        });
+        let block_size = match block_size {
+            0 => overview::block_size(shown_size, blocks_per_row),
+            _ => block_size,
+        };
+        let theme = USE_COLOR.then_some(*THEME);
+        let overview = Overview::new(
+            block_size,
+            blocks_per_row,
+            ARGS.addr_radix,
+            addr_width,
+            ARGS.uppercase,
+            theme,
+        );
+        return Ok(Box::new(overview));
+    }
+
+    Ok(match *STYLE {
+        Style::HexThing => Box::new(HexThing {
+            addr_width: addr_width(shown_size, ARGS.addr_radix),
+            grow_addr: shown_size.is_none(),
+            started,
+            lines: 0,
+            squeeze: (ARGS.autoskip || ARGS.squeeze_count).then(Squeeze::unmarked),
+            skipped: None,
+            repeated: 0,
+            recent: Vec::new(),
+            labeled: vec![false; ARGS.mark.len()],
+            carry: Vec::new(),
+            ahead: VecDeque::new(),
+            regions: match &ARGS.annotate {
+                Some(path) => annotation::load(path)?,
+                None => Vec::new(),
+            },
+            next_region: 0,
+        }),
+        Style::Xxd => Box::new(Xxd::new(*BYTES_PER_LINE, ARGS.uppercase)),
+        Style::Hexdump => Box::new(Hexdump::new(*BYTES_PER_LINE, ARGS.uppercase)),
+        Style::Od => Box::new(Od::new(*BYTES_PER_LINE, ARGS.uppercase)),
+        Style::Plain => Box::new(Plain::new(ARGS.uppercase)),
+    })
+}
+
+/// Dumps `input`, which was opened from `path` unless it came from somewhere else entirely
+fn dump_input(
+    mut input: Input,
+    path: Option<&Path>,
+    writer: &mut impl Write,
+    mode: ReadMode,
+) -> io::Result<()> {
+    let started = Instant::now();
+
+    // The size of a pipe is unknown, so the address width is derived from the
+    // requested length if there is one, and grows with the stream otherwise.
+    // A followed file is only that size as it starts out, so it grows like one
+    let input_size = input.size()?.map(|size| size as usize);
+    let known_size = input_size
+        .filter(|_| mode != ReadMode::Follow)
+        .or(MAX_COUNT.map(|count| *START + count));
+    // Addresses are only as wide as the last one shown needs
+    let shown_size =
+        known_size.map(|size| shown_addr(MAX_COUNT.map_or(size, |count| size.min(*START + count))));
+    let mut renderer = renderer(path, shown_size, started)?;
+
+    // A followed file may still be written to, so its holes can't be trusted.
+    // Gaps between the records of a firmware image are collapsed the same way
+    let holes = match (&input, known_size) {
+        _ if ARGS.expand_holes || mode != ReadMode::Full || !renderer.collapses_holes() => {
+            Vec::new()
+        }
+        (Input::File(file), Some(size)) => sparse::holes(file, size as u64)?,
+        (Input::Image(image), _) => image.gaps(),
+        _ => Vec::new(),
+    };
+    let is_image = matches!(input, Input::Image(_));
+    let mut holes = holes.into_iter().peekable();
+
+    // Mapped files are rendered straight from the mapping, without copying each line.
+    // With io_uring available, large files are read ahead through it instead
+    let map = match (&input, known_size) {
+        (Input::File(file), Some(size)) if mode == ReadMode::Full => {
+            match (ARGS.mmap, size >= MMAP_THRESHOLD && !USE_IO_URING) {
+                (true, _) => Some(Mmap::map(file)?),
+                // Fall back to reading if the file can't be mapped
+                (false, true) => Mmap::map(file).ok(),
+                (false, false) => None,
+            }
+        }
+        _ => None,
+    };
+
+    // An interrupted dump continues where its state file says it got to
+    let start = match &ARGS.resume {
+        Some(state) => resume::load(state)?.map_or(*START, |progress| progress.offset),
+        None => *START,
+    };
+    let mut last_save = Instant::now();
+
+    if let Some(count) = *MAX_COUNT {
+        input.limit((*START + count) as u64);
+    }
+    input.skip(start as u64)?;
+
+    // A resumed dump already has the beginning of its output
+    if start == *START {
+        renderer.begin(writer)?;
+    }
+
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    let input = match input {
+        // Holes, --sample and the bytes between ranges are skipped by seeking, which the read ahead doesn't support
+        Input::File(file)
+            if map.is_none()
+                && holes.peek().is_none()
+                && ARGS.sample.is_none()
+                && RANGES.len() <= 1
+                && mode == ReadMode::Full =>
+        {
+            match file
+                .try_clone()
+                .and_then(|clone| uring::UringReader::new(clone, start as u64))
+            {
+                Ok(uring) => Input::Stream(Box::new(uring), None),
+                // Kernels without io_uring (or sandboxes blocking it) just read normally
+                Err(_) => Input::File(file),
+            }
+        }
+        input => input,
+    };
+
+    // End up to which the input can be skipped through by seeking, rather than reading.
+    // Process memory has no size, but can be seeked anywhere in its address space
+    let seek_end = match &input {
+        _ if mode != ReadMode::Full => None,
+        Input::Memory(_) => Some(usize::MAX),
+        Input::File(_) | Input::Image(_) => input_size,
+        _ => None,
+    };
+    let mut reader = BufReader::new(input);
+
+    let buffer_size = *BYTES_PER_LINE;
+    let mut buffer = vec![0u8; buffer_size];
+
+    let mut current_addr = start;
+    let mut total_bytes_read = start - *START;
+    let mut counts = ClassCounts::default();
+    // Bytes left out between the lines of --sample and between ranges
+    let mut skipped_bytes = 0;
+    let mut ranges = RANGES.iter().peekable();
+
+    loop {
+        while ranges
+            .next_if(|range| range.ends_by(current_addr))
+            .is_some()
+        {}
+        if let Some(range) = ranges.peek().filter(|range| range.start > current_addr) {
+            let len = range.start - current_addr;
+            let skipped = skip_over(&mut reader, map.as_ref(), seek_end, current_addr, len)?;
+            if skipped == 0 {
+                // End of file before the range
+                break;
+            }
+            renderer.skip(writer, shown_addr(current_addr), skipped)?;
+            current_addr += skipped;
+            total_bytes_read += skipped;
+            skipped_bytes += skipped;
+            continue;
+        }
+        let range_left = ranges
+            .peek()
+            .and_then(|range| range.end)
+            .map_or(usize::MAX, |end| end - current_addr);
+
+        // Never read past the requested range, so following doesn't wait for bytes it won't show.
+        // After a partial line, following only fills the rest of it, so the lines after it
+        // stay aligned
+        let line_left = match mode {
+            ReadMode::Follow => buffer_size - total_bytes_read % buffer_size,
+            _ => buffer_size,
+        };
+        let wanted = match *MAX_COUNT {
+            Some(max) => (max - total_bytes_read).min(line_left),
+            None => line_left,
+        }
+        .min(range_left);
+
+        if wanted == 0 {
+            // Out of range
+            break;
+        }
+
+        while holes
+            .next_if(|hole| hole.end <= current_addr as u64)
+            .is_some()
+        {}
+        if let Some(hole) = holes
+            .peek()
+            .filter(|hole| hole.start <= current_addr as u64)
+        {
+            // Only whole lines are collapsed, so the lines after the hole stay aligned
+            let remaining = MAX_COUNT.map_or(usize::MAX, |max| max - total_bytes_read);
+            let hole_len = (hole.end as usize - current_addr)
+                .min(remaining)
+                .min(range_left);
+            let skipped = hole_len / buffer_size * buffer_size;
+
+            if skipped > 0 {
+                renderer.hole(writer, shown_addr(current_addr), skipped, is_image)?;
+                // Gaps read as the fill byte, like when they are expanded
+                let fill = match is_image {
+                    true => GAP_FILL,
+                    false => 0,
+                };
+                counts.add_run(fill, skipped);
+                if map.is_none() {
+                    reader.seek_relative(skipped as i64)?;
+                }
+
+                current_addr += skipped;
+                total_bytes_read += skipped;
+                continue;
+            }
+        }
+
+        let line: &[u8] = match &map {
+            Some(map) => {
+                let start = current_addr.min(map.len());
+                &map[start..(start + wanted).min(map.len())]
+            }
+            None => {
+                let bytes_read = match mode {
+                    ReadMode::Full => read_full(&mut reader, &mut buffer[..wanted])?,
+                    ReadMode::Follow => read_following(&mut reader, &mut buffer[..wanted], writer)?,
+                    ReadMode::Live => {
+                        writer.flush()?;
+                        reader.read(&mut buffer[..wanted])?
+                    }
+                };
+                &buffer[..bytes_read]
+            }
+        };
+        let bytes_read = line.len();
+
+        if bytes_read == 0 {
+            // End of file
+            break;
+        }
+
+        renderer.line(writer, shown_addr(current_addr), line)?;
+        if ARGS.stats {
+            counts.add(line);
+        }
+
+        current_addr += bytes_read;
+        total_bytes_read += bytes_read;
+
+        // The lines between two samples are skipped over without being read where possible
+        if let Some(every) = ARGS.sample.filter(|_| bytes_read == buffer_size) {
+            let remaining = MAX_COUNT.map_or(usize::MAX, |max| max - total_bytes_read);
+            let len = (every.saturating_sub(1) * buffer_size)
+                .min(remaining)
+                .min(range_left - bytes_read);
+            let skipped = skip_over(&mut reader, map.as_ref(), seek_end, current_addr, len)?;
+            if skipped > 0 {
+                renderer.skip(writer, shown_addr(current_addr), skipped)?;
+                current_addr += skipped;
+                total_bytes_read += skipped;
+                skipped_bytes += skipped;
+            }
+        }
+
+        if let Some(state) = &ARGS.resume {
+            if last_save.elapsed() >= RESUME_SAVE_INTERVAL {
+                save_progress(state, current_addr, writer)?;
+                last_save = Instant::now();
+            }
+        }
+    }
+
+    renderer.finish(writer, shown_addr(current_addr))?;
+
+    if ARGS.stats {
+        writeln!(writer, "{}", stats_line(&counts, *USE_COLOR))?;
+    }
+    if ARGS.summary {
+        let shown = current_addr - *START - skipped_bytes;
+        writeln!(
+            writer,
+            "{}",
+            summary_line(shown, *START, current_addr, input_size, *USE_COLOR)
+        )?;
+    }
+
+    if let Some(state) = &ARGS.resume {
+        // A finished dump has nothing left to resume
+        writer.flush()?;
+        match fs::remove_file(state) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Flushes everything dumped before `offset`, then records it in the resume state
+fn save_progress(state: &Path, offset: usize, writer: &mut impl Write) -> io::Result<()> {
+    writer.flush()?;
+    let output_len = match &ARGS.output {
+        Some(output) => Some(fs::metadata(output)?.len()),
+        None => None,
+    };
+    resume::save(state, resume::ResumeState { offset, output_len })
+}
+
+fn reverse_operation(paths: &[PathBuf]) -> io::Result<()> {
+    let mut out_hex: Vec<u8> = Vec::new();
+
+    for path in paths {
+        reverse_file(path, &mut out_hex)?;
+    }
+
+    let ofile = File::create(ARGS.output.clone().expect("No output argument found"))?;
+    let mut writer = BufWriter::new(ofile);
+
+    writer.write_all(&out_hex)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Removes the color codes of a dump written with --color always
+fn strip_ansi(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // Skip to the letter ending the escape sequence
+            '\x1b' => {
+                chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            }
+            c => stripped.push(c),
+        }
+    }
+    stripped
+}
+
+/// Parses bytes written in the selected radix. Each byte has a fixed number of digits,
+/// so the spaces between them (or between groups) don't matter
+fn parse_bytes(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<char> = text
+        .chars()
+        .filter(|&c| !c.is_whitespace() && c != PLACEHOLDER)
+        .collect();
+    digits
+        .chunks(RADIX.byte_digits(ARGS.signed))
+        .map(
+            |digits| match digits.len() == RADIX.byte_digits(ARGS.signed) {
+                true => RADIX.parse_byte(&digits.iter().collect::<String>(), ARGS.signed),
+                false => None,
+            },
+        )
+        .collect()
+}
+
+/// The address and hex columns of a line written without a separator. The address ends
+/// at the first two spaces, and the hex column is as wide as that of a full line
+fn unseparated_columns(line: &str) -> Vec<String> {
+    let hex_width = strip_ansi(&hex_line(&[], 0, &[])).chars().count();
+    let line = line.trim();
+    let (addr, rest) = match ARGS.no_addr {
+        true => (None, line),
+        false => match line.split_once("  ") {
+            Some((addr, rest)) => (Some(addr), rest.trim_start()),
+            None => (Some(line), ""),
+        },
+    };
+    addr.into_iter()
+        .map(str::to_string)
+        .chain([rest.chars().take(hex_width).collect()])
+        .collect()
+}
+
+fn reverse_file(path: &Path, out_hex: &mut Vec<u8>) -> io::Result<()> {
+    let reader = BufReader::new(Input::open(path)?);
+    let start = out_hex.len();
+    // Address of the first line, and whether the last one was the `*` of --autoskip
+    let mut first_addr = None;
+    let mut repeating = false;
+    let mut previous = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let mut line = strip_ansi(&line?);
+        if let Some(start) = line.find(LABEL_PREFIX) {
+            line.truncate(start);
+        }
+        // The frame of --border is left out, with its top and bottom
+        let line = match ARGS.border {
+            true => match line.strip_prefix('│') {
+                Some(inside) => inside.trim_end().trim_end_matches('│').to_string(),
+                None => continue,
+            },
+            false => line,
+        };
+        // So are blank lines and the headers of --annotate
+        if line.trim().is_empty() || line.trim_start().starts_with(SECTION_RULE) {
+            continue;
+        }
+        let parts = match RAW_SPLIT_SYMBOL.is_empty() {
+            true => unseparated_columns(&line),
+            false => line
+                .trim()
+                .split(*RAW_SPLIT_SYMBOL)
+                .map(str::to_string)
+                .collect(),
+        };
+
+        if line.trim() == "*" {
+            repeating = true;
+            continue;
+        }
+        // The count of --squeeze-count needs no address to know where the repeats end
+        if let Some(count) = line
+            .trim()
+            .strip_prefix("* (repeated ")
+            .and_then(|rest| rest.strip_suffix(" times)"))
+        {
+            let Ok(count) = count.parse::<usize>() else {
+                eprintln!("Error: Unable to read the count on line {}", index + 1);
+                std::process::exit(1);
+            };
+            out_hex.extend(previous.repeat(count));
+            continue;
+        }
+
+        // Rulers have no address, and are told apart by their offsets without one
+        let is_ruler = match ARGS.no_addr || RAW_SPLIT_SYMBOL.is_empty() {
+            true => line.trim() == strip_ansi(&ruler_line(0, false)).trim(),
+            false => parts.len() > 1 && parts[0].trim().is_empty(),
+        };
+        if is_ruler {
+            continue;
+        }
+        // Collapsed holes and gaps stand for as many zeros or fill bytes as they say
+        let collapsed = parts.last().and_then(|part| collapsed_bytes(part.trim()));
+        let hex = match collapsed {
+            Some((len, byte)) => vec![byte; len],
+            None => {
+                // --split-every splits the hex column itself
+                let hex_parts = match RAW_SPLIT_SYMBOL.is_empty() {
+                    true => 1,
+                    false => hex_split_count(*BYTES_PER_LINE) + 1,
+                };
+                let first = match ARGS.no_addr {
+                    true => 0,
+                    false => 1,
+                };
+                let hex_str = match parts.len() {
+                    1 => parts[0].to_string(),
+                    len if len >= first + hex_parts => parts[first..first + hex_parts].join(" "),
+                    _ => {
+                        eprintln!(
+                            "Error: Unrecognized input format for reverse operation on line {}",
+                            index + 1
+                        );
+                        std::process::exit(1);
+                    }
+                };
+                let decoded = match ARGS.little_endian {
+                    // Every group is a word written byte swapped
+                    true => hex_str
+                        .split_whitespace()
+                        .map(|word| parse_bytes(word).map(|bytes| bytes.into_iter().rev()))
+                        .collect::<Option<Vec<_>>>()
+                        .map(|words| words.into_iter().flatten().collect()),
+                    false => parse_bytes(&hex_str),
+                };
+                match decoded {
+                    Some(bin) => bin,
+                    None => {
+                        eprintln!(
+                            "Error: Unable to decode hex \"{}\" on line {} ",
+                            hex_str,
+                            index + 1
+                        );
+                        std::process::exit(1);
+                    }
+                }
+            }
+        };
+
+        // The timestamp of --serial comes before the address, and its decimal after it
+        let addr = match parts.len() {
+            1 => None,
+            _ => parts[0]
+                .split_whitespace()
+                .find_map(|word| ARGS.addr_radix.parse_address(word)),
+        };
+        let first_addr = *first_addr.get_or_insert(addr.unwrap_or(0));
+        if repeating {
+            // The lines left out repeat the one before them up to this one
+            let Some(end) = addr.and_then(|addr| addr.checked_sub(first_addr)) else {
+                eprintln!(
+                    "Error: No address to repeat lines up to on line {}",
+                    index + 1
+                );
+                std::process::exit(1);
+            };
+            while out_hex.len() < start + end && !previous.is_empty() {
+                out_hex.extend_from_slice(&previous);
+            }
+            out_hex.truncate(start + end);
+            repeating = false;
+        }
+
+        out_hex.extend_from_slice(&hex);
+        previous = hex;
+    }
+
+    Ok(())
+}
+
+/// Modification time and size of every input, used to notice when one of them changes
+fn input_snapshot(paths: &[PathBuf]) -> io::Result<Vec<(SystemTime, u64)>> {
+    paths
+        .iter()
+        .map(|path| {
+            let metadata = fs::metadata(path)?;
+            Ok((metadata.modified()?, metadata.len()))
+        })
+        .collect()
+}
+
+fn watch_files(paths: &[PathBuf]) -> io::Result<()> {
+    if paths
+        .iter()
+        .any(|path| path.as_os_str() == input::STDIN_PATH)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Standard input can't be watched",
+        ));
+    }
+
+    loop {
+        let snapshot = input_snapshot(paths)?;
+
+        // Clear the screen and move the cursor to the top left corner
+        print!("\x1b[2J\x1b[H");
+        dump_files(paths)?;
+
+        while input_snapshot(paths)? == snapshot {
+            thread::sleep(WATCH_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Draws the bytes of `input` as a PNG image in `output`, or in the terminal without one
+fn visualize_file(
+    input: &Path,
+    output: Option<&Path>,
+    protocol: Option<Protocol>,
+    plot: Plot,
+    width: usize,
+    layout: Layout,
+) -> io::Result<()> {
+    let mut input = Input::open(input)?;
+    let mut picture = Picture::new(plot, width, layout, input.size()?);
+    let mut buffer = vec![0; VISUALIZE_CHUNK_SIZE];
+    loop {
+        match input.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => picture.add(&buffer[..len]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    let bitmap = picture.finish();
+
+    let Some(output) = output else {
+        let protocol = protocol.or_else(Protocol::detect).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "The terminal doesn't seem to support kitty or sixel graphics, pick one with --protocol",
+            )
+        })?;
+        let mut writer = BufWriter::new(io::stdout().lock());
+        match protocol {
+            Protocol::Kitty => {
+                let mut png = Vec::new();
+                png::write(&mut png, &bitmap)?;
+                graphics::kitty(&mut writer, &png)?;
+            }
+            Protocol::Sixel => graphics::sixel(&mut writer, &bitmap)?,
+        }
+        return writer.flush();
+    };
+
+    let mut writer = BufWriter::new(File::create_new(output)?);
+    png::write(&mut writer, &bitmap)?;
+    writer.flush()
+}
+
+fn run() -> io::Result<()> {
+    if let Some(Command::Visualize {
+        input,
+        output,
+        inline: _,
+        protocol,
+        plot,
+        width,
+        layout,
+    }) = &ARGS.command
+    {
+        let output = output.as_deref();
+        return visualize_file(input, output, *protocol, *plot, *width as usize, *layout);
+    }
+
+    let paths = input_paths()?;
+    match (ARGS.reverse, ARGS.watch) {
+        (true, _) => reverse_operation(&paths),
+        (false, true) => watch_files(&paths),
+        (false, false) => dump_files(&paths),
+    }
+}
+
+fn main() {
+    // The colored crate makes its own guess otherwise, which would ignore --color
+    colored::control::set_override(*USE_COLOR);
+
+    match run() {
+        Ok(()) => {}
+        // Quitting the pager, or `head`, before the end of the dump is no error, like
+        // getting to the end of --lines
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
     }
 }
@@ -0,0 +1,52 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
+use crate::{
+    theme::{self, Paint},
+    util::parse_num,
+};
+
+/// A region of the dump picked by --mark, colored and maybe labeled
+#[derive(Debug, Clone)]
+pub struct Mark {
+    /// Addresses of the region as they are shown, both ends included
+    pub range: RangeInclusive<usize>,
+    pub paint: Paint,
+    pub label: Option<String>,
+}
+
+impl Mark {
+    /// Whether any of the `len` bytes starting at `addr` is in the region
+    pub fn overlaps(&self, addr: usize, len: usize) -> bool {
+        len > 0 && addr <= *self.range.end() && *self.range.start() < addr + len
+    }
+}
+
+impl FromStr for Mark {
+    type Err = String;
+
+    /// Parses `START-END:COLOR[:LABEL]`, like `0x100-0x11f:yellow:header`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let range = parts.next().unwrap_or_default();
+        let paint = parts
+            .next()
+            .ok_or_else(|| format!("Expected START-END:COLOR[:LABEL], not \"{s}\""))?;
+        let label = parts.next().map(str::to_string);
+
+        let (start, end) = range
+            .split_once('-')
+            .ok_or_else(|| format!("Expected START-END, not \"{range}\""))?;
+        let address = |text: &str| {
+            parse_num(text.trim()).map_err(|_| format!("Invalid address \"{}\"", text.trim()))
+        };
+        let (start, end) = (address(start)?, address(end)?);
+        if end < start {
+            return Err(format!("Mark {range} ends before it starts"));
+        }
+        Ok(Mark {
+            range: start..=end,
+            paint: theme::parse_paint(paint)?,
+            label,
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::{self, Write};
+
+use crate::render::{printable_char, Renderer};
+
+/// Wraps `text` in a code span that can sit in a table cell
+fn code_span(text: &str) -> String {
+    // Pipes end a cell even inside code spans
+    let text = text.replace('|', "\\|");
+    let delimiter = match text.contains('`') {
+        true => "``",
+        false => "`",
+    };
+    // A single space is stripped from each end of a code span starting and ending with one,
+    // as well as one needed to separate text from a delimiter of backticks
+    let padded = text.starts_with([' ', '`']) || text.ends_with([' ', '`']);
+    match padded && !text.trim().is_empty() {
+        true => format!("{delimiter} {text} {delimiter}"),
+        false => format!("{delimiter}{text}{delimiter}"),
+    }
+}
+
+/// Renders the dump as a GitHub flavored Markdown table
+pub struct Markdown {
+    addr_width: usize,
+    uppercase: bool,
+}
+
+impl Markdown {
+    pub fn new(addr_width: usize, uppercase: bool) -> Self {
+        Markdown {
+            addr_width,
+            uppercase,
+        }
+    }
+}
+
+impl Renderer for Markdown {
+    fn begin(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "| Offset | Hex | ASCII |")?;
+        writeln!(writer, "| --- | --- | --- |")
+    }
+
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        let (addr, hex) = match self.uppercase {
+            true => (
+                format!("0x{addr:0width$X}", width = self.addr_width),
+                bytes
+                    .iter()
+                    .map(|byte| format!("{byte:02X}"))
+                    .collect::<Vec<_>>(),
+            ),
+            false => (
+                format!("0x{addr:0width$x}", width = self.addr_width),
+                bytes
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>(),
+            ),
+        };
+        let ascii: String = bytes.iter().map(|&byte| printable_char(byte)).collect();
+
+        writeln!(
+            writer,
+            "| {} | {} | {} |",
+            code_span(&addr),
+            code_span(&hex.join(" ")),
+            code_span(&ascii)
+        )
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+/// `EIO`, returned by the kernel when reading an unmapped part of another process' memory
+#[cfg(target_os = "linux")]
+const EIO: i32 = 5;
+
+/// The address space of another process, read through `/proc/PID/mem`
+pub struct ProcessMemory {
+    file: File,
+    pid: u32,
+    position: u64,
+}
+
+impl ProcessMemory {
+    #[cfg(target_os = "linux")]
+    pub fn open(pid: u32) -> io::Result<Self> {
+        let file = File::open(format!("/proc/{pid}/mem")).map_err(|e| match e.kind() {
+            io::ErrorKind::PermissionDenied => io::Error::new(
+                e.kind(),
+                format!(
+                    "Not allowed to read the memory of process {pid}, \
+                     try running as root or relaxing /proc/sys/kernel/yama/ptrace_scope"
+                ),
+            ),
+            io::ErrorKind::NotFound => {
+                io::Error::new(e.kind(), format!("No process with pid {pid}"))
+            }
+            _ => e,
+        })?;
+        Ok(ProcessMemory {
+            file,
+            pid,
+            position: 0,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open(pid: u32) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Reading the memory of process {pid} is only supported on Linux"),
+        ))
+    }
+}
+
+impl Read for ProcessMemory {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.file.read(buf) {
+            Ok(read) => {
+                self.position += read as u64;
+                Ok(read)
+            }
+            #[cfg(target_os = "linux")]
+            Err(e) if e.raw_os_error() == Some(EIO) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Address 0x{:x} isn't mapped in process {}",
+                    self.position, self.pid
+                ),
+            )),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Seek for ProcessMemory {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = self.file.seek(pos)?;
+        Ok(self.position)
+    }
+}
@@ -0,0 +1,108 @@
+use std::{fs::File, io, ops::Deref};
+
+#[cfg(all(unix, target_pointer_width = "64"))]
+mod sys {
+    use std::{
+        ffi::c_void,
+        fs::File,
+        io,
+        os::{fd::AsRawFd, raw::c_int},
+        ptr,
+    };
+
+    const PROT_READ: c_int = 1;
+    const MAP_PRIVATE: c_int = 2;
+
+    extern "C" {
+        fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64,
+        ) -> *mut c_void;
+        fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    }
+
+    pub fn map(file: &File, len: usize) -> io::Result<*const u8> {
+        // SAFETY: a fresh read only private mapping doesn't alias any Rust memory
+        let ptr = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        // MAP_FAILED is -1
+        match ptr as isize {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(ptr as *const u8),
+        }
+    }
+
+    pub fn unmap(ptr: *const u8, len: usize) {
+        // SAFETY: ptr and len describe a mapping created by `map` that is no longer used
+        unsafe { munmap(ptr as *mut c_void, len) };
+    }
+}
+
+#[cfg(not(all(unix, target_pointer_width = "64")))]
+mod sys {
+    use std::{fs::File, io};
+
+    pub fn map(_file: &File, _len: usize) -> io::Result<*const u8> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Memory mapping isn't supported on this platform",
+        ))
+    }
+
+    pub fn unmap(_ptr: *const u8, _len: usize) {}
+}
+
+/// A read only memory mapping of a whole file.
+///
+/// The file must not be truncated while it is mapped, or reading the
+/// missing pages kills the process with SIGBUS.
+pub struct Mmap {
+    ptr: *const u8,
+    len: usize,
+}
+
+impl Mmap {
+    pub fn map(file: &File) -> io::Result<Self> {
+        let len = file.metadata()?.len() as usize;
+        if len == 0 {
+            // Empty mappings aren't allowed, and there'd be nothing to read anyway
+            return Ok(Mmap {
+                ptr: std::ptr::NonNull::dangling().as_ptr(),
+                len,
+            });
+        }
+        Ok(Mmap {
+            ptr: sys::map(file, len)?,
+            len,
+        })
+    }
+}
+
+impl Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the mapping covers `len` readable bytes for as long as `self` lives
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            sys::unmap(self.ptr, self.len);
+        }
+    }
+}
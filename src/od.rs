@@ -0,0 +1,68 @@
+use std::io::{self, Write};
+
+use crate::render::{printable_char, Renderer, Squeeze};
+
+/// Renders lines exactly like `od -Ax -tx1z`, including its `*` for repeated lines
+pub struct Od {
+    bytes_per_line: usize,
+    uppercase: bool,
+    squeeze: Squeeze,
+}
+
+impl Od {
+    pub fn new(bytes_per_line: usize, uppercase: bool) -> Self {
+        Od {
+            bytes_per_line,
+            uppercase,
+            squeeze: Squeeze::default(),
+        }
+    }
+}
+
+impl Renderer for Od {
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        if self.squeeze.repeats(writer, bytes)? {
+            return Ok(());
+        }
+
+        let hex: String = bytes
+            .iter()
+            .map(|byte| match self.uppercase {
+                true => format!(" {byte:02X}"),
+                false => format!(" {byte:02x}"),
+            })
+            .collect();
+        let ascii: String = bytes.iter().map(|&byte| printable_char(byte)).collect();
+
+        writeln!(
+            writer,
+            "{addr:06x}{hex:<width$}  >{ascii}<",
+            width = self.bytes_per_line * 3
+        )
+    }
+
+    fn collapses_holes(&self) -> bool {
+        true
+    }
+
+    /// A hole is a run of identical zero lines, so at most two of them are ever shown
+    fn hole(
+        &mut self,
+        writer: &mut dyn Write,
+        addr: usize,
+        len: usize,
+        _is_gap: bool,
+    ) -> io::Result<()> {
+        let zeros = vec![0u8; self.bytes_per_line];
+        self.line(writer, addr, &zeros)?;
+        if len > self.bytes_per_line {
+            self.line(writer, addr + self.bytes_per_line, &zeros)?;
+        }
+        Ok(())
+    }
+
+    /// od ends with the address past the last byte, even for empty input
+    fn finish(&mut self, writer: &mut dyn Write, end: usize) -> io::Result<()> {
+        writeln!(writer, "{end:06x}")
+    }
+}
@@ -0,0 +1,192 @@
+use std::io::{self, Write};
+
+use colored::{Color, Colorize};
+
+use crate::entropy;
+use crate::image::GAP_FILL;
+use crate::radix::Radix;
+use crate::render::{ByteClass, Renderer};
+use crate::theme::Theme;
+
+/// Characters of blocks from the lowest entropy to the highest
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// Blocks on a row when the width of the terminal is unknown
+pub const DEFAULT_BLOCKS_PER_ROW: usize = 64;
+
+/// Rows a file of known size is fitted in when no block size is given
+const TARGET_ROWS: usize = 32;
+
+/// Smallest block size picked for a file of known size
+const MIN_BLOCK_SIZE: usize = 16;
+
+/// Block size for an input of unknown size
+const DEFAULT_BLOCK_SIZE: usize = 4096;
+
+/// Block size fitting `size` bytes in about `TARGET_ROWS` rows of `blocks_per_row`,
+/// as a power of two so the addresses of the rows stay round
+pub fn block_size(size: Option<usize>, blocks_per_row: usize) -> usize {
+    match size {
+        Some(size) => size
+            .div_ceil(blocks_per_row * TARGET_ROWS)
+            .max(MIN_BLOCK_SIZE)
+            .next_power_of_two(),
+        None => DEFAULT_BLOCK_SIZE,
+    }
+}
+
+/// Renders a character for every block of bytes rather than the bytes themselves, its shade
+/// being the entropy of the block and its color that of the class most of its bytes are in
+pub struct Overview {
+    block_size: usize,
+    blocks_per_row: usize,
+    radix: Radix,
+    addr_width: usize,
+    uppercase: bool,
+    /// Colors of the classes, unless the output is uncolored
+    theme: Option<Theme>,
+    /// Address of the block being counted, once the first line came
+    addr: Option<usize>,
+    /// How often each value occurs in the block being counted
+    counts: [usize; 256],
+    filled: usize,
+    /// Blocks written on the current row
+    column: usize,
+}
+
+impl Overview {
+    pub fn new(
+        block_size: usize,
+        blocks_per_row: usize,
+        radix: Radix,
+        addr_width: usize,
+        uppercase: bool,
+        theme: Option<Theme>,
+    ) -> Self {
+        Overview {
+            block_size,
+            blocks_per_row,
+            radix,
+            addr_width,
+            uppercase,
+            theme,
+            addr: None,
+            counts: [0; 256],
+            filled: 0,
+            column: 0,
+        }
+    }
+
+    fn paint(&self, text: String, color: impl Fn(&Theme) -> Option<Color>) -> String {
+        match self.theme.as_ref().and_then(color) {
+            Some(color) => text.color(color).to_string(),
+            None => text,
+        }
+    }
+
+    /// Counts `count` more copies of `byte`, writing every block that fills up
+    fn add_run(&mut self, writer: &mut dyn Write, byte: u8, mut count: usize) -> io::Result<()> {
+        while count > 0 {
+            let taken = count.min(self.block_size - self.filled);
+            self.counts[byte as usize] += taken;
+            self.filled += taken;
+            count -= taken;
+            if self.filled == self.block_size {
+                self.write_block(writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the character of the block counted so far, after the address if it starts a row
+    fn write_block(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        let addr = self.addr.unwrap_or_default();
+        if self.column == 0 {
+            let addr = self.radix.address(addr, self.addr_width, self.uppercase);
+            let prefix = self.paint(format!("{addr} │"), |theme| theme.dim);
+            write!(writer, "{prefix} ")?;
+        }
+
+        let mut classes = [0usize; ByteClass::ALL.len()];
+        for (byte, &count) in self.counts.iter().enumerate() {
+            classes[ByteClass::of(byte as u8) as usize] += count;
+        }
+        // The first class wins a tie, so half empty blocks look empty
+        let class = ByteClass::ALL
+            .into_iter()
+            .rev()
+            .max_by_key(|&class| classes[class as usize])
+            .unwrap_or(ByteClass::Null);
+
+        let share = entropy::shannon_counts(&self.counts) / entropy::max_shannon(self.filled);
+        let shade = SHADES[((share * SHADES.len() as f64) as usize).min(SHADES.len() - 1)];
+        write!(
+            writer,
+            "{}",
+            self.paint(shade.to_string(), |theme| theme.class(class))
+        )?;
+
+        self.addr = Some(addr + self.filled);
+        self.counts = [0; 256];
+        self.filled = 0;
+        self.column += 1;
+        if self.column == self.blocks_per_row {
+            writeln!(writer)?;
+            self.column = 0;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for Overview {
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        self.addr.get_or_insert(addr);
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let (block, later) = rest.split_at(rest.len().min(self.block_size - self.filled));
+            for &byte in block {
+                self.counts[byte as usize] += 1;
+            }
+            self.filled += block.len();
+            if self.filled == self.block_size {
+                self.write_block(writer)?;
+            }
+            rest = later;
+        }
+        Ok(())
+    }
+
+    fn collapses_holes(&self) -> bool {
+        true
+    }
+
+    fn hole(
+        &mut self,
+        writer: &mut dyn Write,
+        addr: usize,
+        len: usize,
+        is_gap: bool,
+    ) -> io::Result<()> {
+        self.addr.get_or_insert(addr);
+        let byte = match is_gap {
+            true => GAP_FILL,
+            false => 0,
+        };
+        self.add_run(writer, byte, len)
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        if self.filled > 0 {
+            self.write_block(writer)?;
+        }
+        if self.column > 0 {
+            writeln!(writer)?;
+        }
+        let shades = SHADES.map(String::from).join("");
+        let legend = format!(
+            "{} bytes to a character, entropy from {shades}, colored by the most common class",
+            self.block_size
+        );
+        writeln!(writer, "{}", self.paint(legend, |theme| theme.dim))
+    }
+}
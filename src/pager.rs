@@ -0,0 +1,106 @@
+use std::env;
+use std::io::{self, BufWriter, Stdout, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use crate::terminal;
+
+/// Pager used when $PAGER isn't set, keeping the colors of the dump
+const DEFAULT_PAGER: &str = "less -R";
+
+enum State {
+    /// Holding back the output until it is known to be longer than a screen
+    Holding(Vec<u8>),
+    Paging(Child, BufWriter<ChildStdin>),
+    /// Writing to standard output, with no pager to run
+    Direct,
+}
+
+/// Standard output of a terminal, piped through $PAGER once it gets longer than a screen.
+/// Dropping it writes out what was held back, or waits for the pager to be quit
+pub struct Pager {
+    rows: usize,
+    lines: usize,
+    state: State,
+    stdout: Stdout,
+}
+
+impl Pager {
+    /// Pager for the terminal, unless its height can't be told
+    pub fn new() -> Option<Self> {
+        Some(Pager {
+            rows: terminal::height()?,
+            lines: 0,
+            state: State::Holding(Vec::new()),
+            stdout: io::stdout(),
+        })
+    }
+
+    /// Runs the pager with what was held back, or writes that out if no pager will run,
+    /// like with an empty $PAGER or one that doesn't exist
+    fn start(&mut self) -> io::Result<()> {
+        let command = env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+        let mut words = command.split_whitespace();
+        let child = match words.next() {
+            Some(program) => Command::new(program)
+                .args(words)
+                .stdin(Stdio::piped())
+                .spawn()
+                .ok(),
+            None => None,
+        };
+        let held = match std::mem::replace(&mut self.state, State::Direct) {
+            State::Holding(held) => held,
+            _ => Vec::new(),
+        };
+        if let Some(mut child) = child {
+            let stdin = child.stdin.take().expect("the stdin of the pager is piped");
+            self.state = State::Paging(child, BufWriter::new(stdin));
+        }
+        self.write_all(&held)
+    }
+}
+
+impl Write for Pager {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.state {
+            State::Holding(held) => {
+                held.extend_from_slice(buf);
+                self.lines += buf.iter().filter(|&&byte| byte == b'\n').count();
+                // The prompt of the pager takes up a row as well
+                if self.lines >= self.rows {
+                    self.start()?;
+                }
+                Ok(buf.len())
+            }
+            State::Paging(_, stdin) => stdin.write(buf),
+            State::Direct => self.stdout.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.state {
+            State::Holding(_) => Ok(()),
+            State::Paging(_, stdin) => stdin.flush(),
+            State::Direct => self.stdout.flush(),
+        }
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        match std::mem::replace(&mut self.state, State::Direct) {
+            State::Holding(held) => {
+                let _ = self.stdout.write_all(&held);
+                let _ = self.stdout.flush();
+            }
+            State::Paging(mut child, stdin) => {
+                // The pager only finishes once its input is closed
+                drop(stdin);
+                let _ = child.wait();
+            }
+            State::Direct => {
+                let _ = self.stdout.flush();
+            }
+        }
+    }
+}
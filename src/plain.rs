@@ -0,0 +1,23 @@
+use std::io::{self, Write};
+
+use crate::render::Renderer;
+
+/// Renders only the hex digits, like `xxd -ps`
+pub struct Plain {
+    uppercase: bool,
+}
+
+impl Plain {
+    pub fn new(uppercase: bool) -> Self {
+        Plain { uppercase }
+    }
+}
+
+impl Renderer for Plain {
+    fn line(&mut self, writer: &mut dyn Write, _addr: usize, bytes: &[u8]) -> io::Result<()> {
+        match self.uppercase {
+            true => writeln!(writer, "{}", hex::encode_upper(bytes)),
+            false => writeln!(writer, "{}", hex::encode(bytes)),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::{self, Write};
+
+use crate::checksum::{Adler32, Crc32};
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// Bit depth 8 and color type 2, red, green and blue for every pixel
+const RGB: [u8; 2] = [8, 2];
+/// Largest block of a DEFLATE stream that is stored without compression
+const MAX_STORED_BLOCK: usize = 0xffff;
+
+/// An image of RGB pixels, row by row
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl Bitmap {
+    /// An image filled with `color`
+    pub fn new(width: usize, height: usize, color: [u8; 3]) -> Self {
+        Bitmap {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: [u8; 3]) {
+        self.pixels[y * self.width + x] = color;
+    }
+}
+
+fn write_chunk(writer: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let mut crc = Crc32::new();
+    crc.update(kind);
+    crc.update(data);
+
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    writer.write_all(&crc.finish().to_be_bytes())
+}
+
+/// Wraps `data` in a zlib stream of stored blocks, which every decoder reads without effort
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32 KiB window, at no particular compression level
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        stream.push(last as u8);
+        stream.extend_from_slice(&len.to_le_bytes());
+        stream.extend_from_slice(&(!len).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+
+    let mut adler = Adler32::new();
+    adler.update(data);
+    stream.extend_from_slice(&adler.finish().to_be_bytes());
+    stream
+}
+
+/// Writes `bitmap` as a PNG file
+pub fn write(writer: &mut impl Write, bitmap: &Bitmap) -> io::Result<()> {
+    if bitmap.width == 0 || bitmap.height == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "A PNG image needs at least one pixel",
+        ));
+    }
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&(bitmap.width as u32).to_be_bytes());
+    header.extend_from_slice(&(bitmap.height as u32).to_be_bytes());
+    header.extend_from_slice(&RGB);
+    // Default compression, filtering and no interlacing
+    header.extend_from_slice(&[0, 0, 0]);
+
+    // Every row starts with the type of its filter, none here
+    let mut scanlines = Vec::with_capacity(bitmap.height * (bitmap.width * 3 + 1));
+    for row in bitmap.pixels.chunks(bitmap.width) {
+        scanlines.push(0);
+        scanlines.extend(row.iter().flatten());
+    }
+
+    writer.write_all(SIGNATURE)?;
+    write_chunk(writer, b"IHDR", &header)?;
+    write_chunk(writer, b"IDAT", &zlib_stored(&scanlines))?;
+    write_chunk(writer, b"IEND", &[])
+}
@@ -0,0 +1,95 @@
+/// Base in which the bytes and addresses of the default style are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Radix {
+    Hex,
+    /// Zero padded values from 000 to 255
+    #[value(alias = "dec")]
+    Decimal,
+    /// Three octal digits, as in `od -b`
+    #[value(alias = "oct")]
+    Octal,
+    #[value(alias = "bin")]
+    Binary,
+}
+
+impl Radix {
+    fn base(self) -> u32 {
+        match self {
+            Radix::Hex => 16,
+            Radix::Decimal => 10,
+            Radix::Octal => 8,
+            Radix::Binary => 2,
+        }
+    }
+
+    /// Number of digits needed to write `num`
+    pub fn digits(self, num: usize) -> usize {
+        let mut digits = 1;
+        let mut rest = num / self.base() as usize;
+        while rest > 0 {
+            digits += 1;
+            rest /= self.base() as usize;
+        }
+        digits
+    }
+
+    /// Largest number that can be written with `width` digits
+    pub fn largest(self, width: usize) -> usize {
+        (self.base() as usize)
+            .checked_pow(width as u32)
+            .map_or(usize::MAX, |limit| limit - 1)
+    }
+
+    /// `addr` padded to `width` digits, after the prefix of its base
+    pub fn address(self, addr: usize, width: usize, uppercase: bool) -> String {
+        match (self, uppercase) {
+            (Radix::Hex, false) => format!("0x{addr:0width$x}"),
+            (Radix::Hex, true) => format!("0x{addr:0width$X}"),
+            (Radix::Decimal, _) => format!("{addr:0width$}"),
+            (Radix::Octal, _) => format!("0o{addr:0width$o}"),
+            (Radix::Binary, _) => format!("0b{addr:0width$b}"),
+        }
+    }
+
+    /// The address written by `address`, if `text` is one
+    pub fn parse_address(self, text: &str) -> Option<usize> {
+        let digits = match self {
+            Radix::Hex => text.strip_prefix("0x")?,
+            Radix::Decimal => text,
+            Radix::Octal => text.strip_prefix("0o")?,
+            Radix::Binary => text.strip_prefix("0b")?,
+        };
+        usize::from_str_radix(digits, self.base()).ok()
+    }
+
+    /// Number of characters every byte is padded to, counting the sign of `signed` decimals
+    pub fn byte_digits(self, signed: bool) -> usize {
+        match self {
+            Radix::Hex => 2,
+            Radix::Decimal if signed => 4,
+            Radix::Decimal | Radix::Octal => 3,
+            Radix::Binary => 8,
+        }
+    }
+
+    /// The byte in this base, from -128 to +127 as `signed` decimals
+    pub fn byte(self, byte: u8, uppercase: bool, signed: bool) -> String {
+        match (self, uppercase) {
+            (Radix::Hex, false) => format!("{byte:02x}"),
+            (Radix::Hex, true) => format!("{byte:02X}"),
+            // The sign is always written so every byte has the same width
+            (Radix::Decimal, _) if signed => format!("{:+04}", byte as i8),
+            (Radix::Decimal, _) => format!("{byte:03}"),
+            (Radix::Octal, _) => format!("{byte:03o}"),
+            (Radix::Binary, _) => format!("{byte:08b}"),
+        }
+    }
+
+    /// The byte written as `digits`, if they are valid in this base
+    pub fn parse_byte(self, digits: &str, signed: bool) -> Option<u8> {
+        match (self, signed) {
+            (Radix::Decimal, true) => digits.parse::<i8>().ok().map(|byte| byte as u8),
+            _ => u8::from_str_radix(digits, self.base()).ok(),
+        }
+    }
+}
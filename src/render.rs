@@ -0,0 +1,233 @@
+use std::io::{self, Write};
+
+/// Layout of the dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Style {
+    /// Colored columns split by bars, with glyphs for control characters
+    HexThing,
+    /// Byte for byte the output of `xxd`
+    Xxd,
+    /// Byte for byte the output of `hexdump -C`
+    Hexdump,
+    /// Byte for byte the output of `od -Ax -tx1z`
+    Od,
+    /// Nothing but the hex digits, like `xxd -ps`
+    Plain,
+}
+
+/// Kind of output, either a text dump or structured data for other programs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// A text dump in one of the styles
+    Text,
+    /// A JSON document with the rows of the input, or an array of them for several inputs
+    Json,
+    /// A JSON object per row, each on a line of its own
+    Jsonl,
+    /// Comma separated values, with a row for every byte
+    Csv,
+    /// Tab separated values, with a row for every byte
+    Tsv,
+    /// A standalone HTML page of a single input, with the colors of the terminal
+    Html,
+    /// An SVG image of a single input with the colors of the terminal, best kept to a small
+    /// --byte-range
+    Svg,
+    /// A GitHub flavored Markdown table
+    Markdown,
+    /// Escapes like `\x41\x42`, or numbers like `0x41, 0x42` with --sep
+    Escaped,
+    /// Intel HEX records of a single input, for flashing tools
+    Ihex,
+    /// Motorola S-records of a single input, for flashing tools
+    Srec,
+    /// UF2 blocks of a single input, for bootloaders that show up as a USB drive
+    Uf2,
+}
+
+impl Format {
+    /// Whether every line is written out as it comes, rather than held back or tied to the
+    /// lines around it, so that a dump cut short is the start of the whole one
+    pub fn streams(self) -> bool {
+        matches!(
+            self,
+            Format::Text | Format::Jsonl | Format::Csv | Format::Tsv | Format::Markdown
+        )
+    }
+
+    /// Whether the output is a single document, which the dump of a second input can't follow
+    pub fn is_single(self) -> bool {
+        matches!(
+            self,
+            Format::Html | Format::Svg | Format::Ihex | Format::Srec | Format::Uf2
+        )
+    }
+}
+
+/// What decides the color of a byte in the default style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorBy {
+    /// Its class: null, whitespace, printable, control or high
+    Class,
+    /// The entropy of the bytes around it, from blue for repeats to red for random
+    Entropy,
+    /// Its value, on a 24-bit gradient from blue for 0x00 through green to red for 0xff
+    Value,
+}
+
+/// Category of a byte, which decides how it is colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteClass {
+    Null,
+    Whitespace,
+    Printable,
+    Control,
+    High,
+}
+
+impl ByteClass {
+    pub const ALL: [ByteClass; 5] = [
+        ByteClass::Null,
+        ByteClass::Whitespace,
+        ByteClass::Printable,
+        ByteClass::Control,
+        ByteClass::High,
+    ];
+
+    pub fn of(byte: u8) -> Self {
+        match byte {
+            0 => ByteClass::Null,
+            9 | 10 | 13 | 32 => ByteClass::Whitespace,
+            33..=126 => ByteClass::Printable,
+            128..=255 => ByteClass::High,
+            _ => ByteClass::Control,
+        }
+    }
+
+    /// Name of the class, as used for CSS classes
+    pub fn name(self) -> &'static str {
+        match self {
+            ByteClass::Null => "null",
+            ByteClass::Whitespace => "whitespace",
+            ByteClass::Printable => "printable",
+            ByteClass::Control => "control",
+            ByteClass::High => "high",
+        }
+    }
+}
+
+/// Number of bytes seen of each class
+#[derive(Default)]
+pub struct ClassCounts([usize; ByteClass::ALL.len()]);
+
+impl ClassCounts {
+    pub fn add(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0[ByteClass::of(byte) as usize] += 1;
+        }
+    }
+
+    /// Counts a run of `len` copies of `byte`, such as a hole or gap that was never read
+    pub fn add_run(&mut self, byte: u8, len: usize) {
+        self.0[ByteClass::of(byte) as usize] += len;
+    }
+
+    pub fn count(&self, class: ByteClass) -> usize {
+        self.0[class as usize]
+    }
+
+    pub fn total(&self) -> usize {
+        self.0.iter().sum()
+    }
+}
+
+/// Character shown for a byte by the classic tools, which print a dot for anything unprintable
+pub fn printable_char(byte: u8) -> char {
+    match byte {
+        0x20..=0x7e => byte as char,
+        _ => '.',
+    }
+}
+
+/// Turns the lines of a dump into output in one style
+pub trait Renderer {
+    /// Writes whatever comes before the first line
+    fn begin(&mut self, _writer: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Writes the `bytes` of one line, the first of them being at address `addr`
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()>;
+
+    /// Whether runs of zeros known in advance (sparse file holes, firmware gaps) can be
+    /// shown as a single line with `hole`, rather than read and written line by line
+    fn collapses_holes(&self) -> bool {
+        false
+    }
+
+    /// Writes a collapsed run of `len` bytes starting at `addr`
+    fn hole(
+        &mut self,
+        _writer: &mut dyn Write,
+        _addr: usize,
+        _len: usize,
+        _is_gap: bool,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Writes a marker for the `len` bytes starting at `addr` left out between the lines of
+    /// --sample
+    fn skip(&mut self, _writer: &mut dyn Write, _addr: usize, _len: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Writes whatever follows the last line, `end` being the address just past it
+    fn finish(&mut self, _writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Tracks repeated lines for the styles that replace them with a single `*`
+#[derive(Default)]
+pub struct Squeeze {
+    /// Bytes of the last line shown
+    previous: Option<Vec<u8>>,
+    squeezing: bool,
+    /// Whether the caller marks runs of repeats itself, rather than with a `*`
+    unmarked: bool,
+}
+
+impl Squeeze {
+    /// Tracks repeated lines without writing anything for them
+    pub fn unmarked() -> Self {
+        Squeeze {
+            unmarked: true,
+            ..Squeeze::default()
+        }
+    }
+
+    /// Whether `bytes` repeats the last line, writing the `*` that starts a run of repeats
+    pub fn repeats(&mut self, writer: &mut dyn Write, bytes: &[u8]) -> io::Result<bool> {
+        if self.previous.as_deref() == Some(bytes) {
+            if !self.squeezing && !self.unmarked {
+                writeln!(writer, "*")?;
+                self.squeezing = true;
+            }
+            return Ok(true);
+        }
+        self.squeezing = false;
+        self.previous = Some(bytes.to_vec());
+        Ok(false)
+    }
+
+    /// Whether any line was shown yet
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_none()
+    }
+
+    /// Bytes of the last line shown
+    pub fn previous(&self) -> Option<&[u8]> {
+        self.previous.as_deref()
+    }
+}
@@ -0,0 +1,68 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io,
+    path::Path,
+};
+
+/// Progress of an interrupted dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResumeState {
+    /// Address of the first byte that hasn't been dumped yet
+    pub offset: usize,
+    /// Length of the output file when `offset` was recorded, if dumping to a file
+    pub output_len: Option<u64>,
+}
+
+fn invalid(state: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Malformed resume state in {}", state.display()),
+    )
+}
+
+/// Reads the state left by an earlier run, if there is one
+pub fn load(state: &Path) -> io::Result<Option<ResumeState>> {
+    let contents = match fs::read_to_string(state) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut offset = None;
+    let mut output_len = None;
+    for line in contents.lines() {
+        match line.split_once('=') {
+            Some(("offset", value)) => offset = value.parse().ok(),
+            Some(("output_len", value)) => output_len = value.parse().ok(),
+            _ => return Err(invalid(state)),
+        }
+    }
+
+    match offset {
+        Some(offset) => Ok(Some(ResumeState { offset, output_len })),
+        None => Err(invalid(state)),
+    }
+}
+
+/// Records progress, replacing the state file atomically so a crash can't leave half of it
+pub fn save(state: &Path, progress: ResumeState) -> io::Result<()> {
+    let mut contents = format!("offset={}\n", progress.offset);
+    if let Some(len) = progress.output_len {
+        contents += &format!("output_len={len}\n");
+    }
+
+    let mut temp = state.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(&temp, state)
+}
+
+/// Opens the output of a resumed dump for appending, cutting off anything written
+/// after the state was last saved
+pub fn reopen_output(output: &Path, progress: ResumeState) -> io::Result<File> {
+    let file = OpenOptions::new().append(true).open(output)?;
+    if let Some(len) = progress.output_len {
+        file.set_len(len)?;
+    }
+    Ok(file)
+}
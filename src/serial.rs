@@ -0,0 +1,46 @@
+use std::{
+    fs::File,
+    io,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Flag `stty` uses to select the device to configure
+#[cfg(any(target_os = "macos", target_os = "freebsd", target_os = "openbsd"))]
+const STTY_DEVICE_FLAG: &str = "-f";
+#[cfg(not(any(target_os = "macos", target_os = "freebsd", target_os = "openbsd")))]
+const STTY_DEVICE_FLAG: &str = "-F";
+
+/// Opens a serial device for reading, switching it to raw mode at `baud` bits per second
+#[cfg(unix)]
+pub fn open(device: &Path, baud: u32) -> io::Result<File> {
+    // The device has to stay open while configuring it, or some drivers reset the settings
+    let file = File::open(device)?;
+
+    let status = Command::new("stty")
+        .arg(STTY_DEVICE_FLAG)
+        .arg(device)
+        .arg(baud.to_string())
+        .args(["raw", "-echo"])
+        .stdin(Stdio::null())
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "Unable to configure {} for {baud} baud",
+            device.display()
+        )));
+    }
+
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+pub fn open(device: &Path, _baud: u32) -> io::Result<File> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "Serial input isn't supported on this platform: {}",
+            device.display()
+        ),
+    ))
+}
@@ -0,0 +1,42 @@
+use std::{
+    io::{self, Read},
+    net::{TcpListener, TcpStream},
+};
+
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+/// Prefix that marks a socket address as a unix socket path
+const UNIX_PREFIX: &str = "unix:";
+
+#[cfg(unix)]
+fn unix_path(addr: &str) -> Option<&str> {
+    addr.strip_prefix(UNIX_PREFIX)
+}
+
+#[cfg(not(unix))]
+fn unix_path(addr: &str) -> Option<&str> {
+    let _ = addr;
+    None
+}
+
+/// Connects to a TCP address like "localhost:1234", or a unix socket like "unix:/tmp/sock"
+pub fn connect(addr: &str) -> io::Result<Box<dyn Read>> {
+    #[cfg(unix)]
+    if let Some(path) = unix_path(addr) {
+        return Ok(Box::new(UnixStream::connect(path)?));
+    }
+    Ok(Box::new(TcpStream::connect(addr)?))
+}
+
+/// Listens on `addr` (in the same format as [`connect`]) and waits for a single connection
+pub fn listen(addr: &str) -> io::Result<Box<dyn Read>> {
+    #[cfg(unix)]
+    if let Some(path) = unix_path(addr) {
+        let (stream, _) = UnixListener::bind(path)?.accept()?;
+        return Ok(Box::new(stream));
+    }
+
+    let (stream, _) = TcpListener::bind(addr)?.accept()?;
+    Ok(Box::new(stream))
+}
@@ -0,0 +1,81 @@
+use std::{fs::File, io, ops::Range};
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+mod sys {
+    use std::{
+        fs::File,
+        io,
+        os::{fd::AsRawFd, raw::c_int},
+    };
+
+    const SEEK_SET: c_int = 0;
+    const SEEK_DATA: c_int = 3;
+    const SEEK_HOLE: c_int = 4;
+    const EINVAL: i32 = 22;
+    const ENXIO: i32 = 6;
+
+    extern "C" {
+        fn lseek(fd: c_int, offset: i64, whence: c_int) -> i64;
+    }
+
+    fn seek(file: &File, offset: u64, whence: c_int) -> io::Result<u64> {
+        // SAFETY: lseek only moves the offset of a file descriptor we own
+        match unsafe { lseek(file.as_raw_fd(), offset as i64, whence) } {
+            -1 => Err(io::Error::last_os_error()),
+            position => Ok(position as u64),
+        }
+    }
+
+    /// Offset of the first data at or after `offset`, or `None` if only a hole follows
+    pub fn next_data(file: &File, offset: u64) -> io::Result<Option<u64>> {
+        match seek(file, offset, SEEK_DATA) {
+            Ok(position) => Ok(Some(position)),
+            Err(e) if e.raw_os_error() == Some(ENXIO) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn next_hole(file: &File, offset: u64) -> io::Result<u64> {
+        seek(file, offset, SEEK_HOLE)
+    }
+
+    pub fn rewind(file: &File) -> io::Result<()> {
+        seek(file, 0, SEEK_SET).map(|_| ())
+    }
+
+    /// Whether an error means the filesystem can't report holes
+    pub fn is_unsupported(e: &io::Error) -> bool {
+        e.raw_os_error() == Some(EINVAL)
+    }
+}
+
+/// Finds the holes of a sparse file: ranges of zeros that the filesystem doesn't store
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+pub fn holes(file: &File, size: u64) -> io::Result<Vec<Range<u64>>> {
+    let mut holes = Vec::new();
+    let mut offset = 0;
+
+    while offset < size {
+        let data = match sys::next_data(file, offset) {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                holes.push(offset..size);
+                break;
+            }
+            Err(e) if sys::is_unsupported(&e) => break,
+            Err(e) => return Err(e),
+        };
+        if data > offset {
+            holes.push(offset..data);
+        }
+        offset = sys::next_hole(file, data)?;
+    }
+
+    sys::rewind(file)?;
+    Ok(holes)
+}
+
+#[cfg(not(all(target_os = "linux", target_pointer_width = "64")))]
+pub fn holes(_file: &File, _size: u64) -> io::Result<Vec<Range<u64>>> {
+    Ok(Vec::new())
+}
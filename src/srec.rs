@@ -0,0 +1,181 @@
+use std::io::{self, Write};
+
+use crate::{image::Image, render::Renderer};
+
+fn invalid(line: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid S-record on line {line}: {message}"),
+    )
+}
+
+/// Width of the address field of each record type, or None for unknown types
+fn address_len(record_type: u8) -> Option<usize> {
+    match record_type {
+        b'0' | b'1' | b'5' | b'9' => Some(2),
+        b'2' | b'6' | b'8' => Some(3),
+        b'3' | b'7' => Some(4),
+        _ => None,
+    }
+}
+
+/// Assembles the image described by the records of a Motorola S-record (S19/S28/S37) file
+pub fn parse(text: &[u8]) -> io::Result<Image> {
+    let text = String::from_utf8_lossy(text);
+    let mut records = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = line
+            .strip_prefix('S')
+            .ok_or_else(|| invalid(line_number, "missing 'S' start code"))?;
+        let record_type = *record.as_bytes().first().unwrap_or(&0);
+        let address_len = address_len(record_type).ok_or_else(|| {
+            invalid(
+                line_number,
+                &format!("unknown record type S{}", record_type as char),
+            )
+        })?;
+
+        let bytes =
+            hex::decode(&record[1..]).map_err(|_| invalid(line_number, "not hex digits"))?;
+        // The byte count covers the address, data and checksum
+        if bytes.len() < address_len + 2 || bytes.len() != bytes[0] as usize + 1 {
+            return Err(invalid(line_number, "wrong byte count"));
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xff {
+            return Err(invalid(line_number, "checksum mismatch"));
+        }
+
+        let address = bytes[1..1 + address_len]
+            .iter()
+            .fold(0u64, |address, byte| address << 8 | *byte as u64);
+        let data = &bytes[1 + address_len..bytes.len() - 1];
+
+        match record_type {
+            b'1' | b'2' | b'3' => records.push((address, data.to_vec())),
+            // Termination records end the file
+            b'7' | b'8' | b'9' => break,
+            // Headers and record counts don't affect the image
+            _ => {}
+        }
+    }
+
+    Ok(Image::from_records(records))
+}
+
+fn record(record_type: u8, address: u64, address_len: usize, data: &[u8]) -> String {
+    let mut bytes = vec![(address_len + data.len() + 1) as u8];
+    bytes.extend_from_slice(&address.to_be_bytes()[8 - address_len..]);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(!sum);
+    format!("S{}{}", record_type as char, hex::encode_upper(bytes))
+}
+
+/// Renders the bytes as Motorola S-records, loaded at `load_address` onwards
+pub struct Srec {
+    header: String,
+    record_size: usize,
+    load_address: u64,
+    /// Runs of contiguous data, held back as the address width depends on the highest address
+    runs: Vec<(u64, Vec<u8>)>,
+}
+
+impl Srec {
+    pub fn new(header: String, record_size: usize, load_address: u64) -> Self {
+        Srec {
+            header,
+            record_size,
+            load_address,
+            runs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, address: u64, bytes: &[u8]) {
+        match self.runs.last_mut() {
+            Some((start, run)) if *start + run.len() as u64 == address => {
+                run.extend_from_slice(bytes)
+            }
+            _ => self.runs.push((address, bytes.to_vec())),
+        }
+    }
+}
+
+impl Renderer for Srec {
+    fn line(&mut self, _writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        self.push(self.load_address + addr as u64, bytes);
+        Ok(())
+    }
+
+    fn collapses_holes(&self) -> bool {
+        true
+    }
+
+    /// Gaps between records stay gaps, while the zeros of sparse file holes are written out
+    fn hole(
+        &mut self,
+        _writer: &mut dyn Write,
+        addr: usize,
+        len: usize,
+        is_gap: bool,
+    ) -> io::Result<()> {
+        if !is_gap {
+            self.push(self.load_address + addr as u64, &vec![0; len]);
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        let end = self
+            .runs
+            .last()
+            .map_or(0, |(start, run)| start + run.len() as u64);
+        // The narrowest of S1, S2 and S3 records that reaches the last byte
+        let (data_type, end_type, address_len) = match end {
+            0..=0x1_0000 => (b'1', b'9', 2),
+            0x1_0001..=0x100_0000 => (b'2', b'8', 3),
+            0x100_0001..=0x1_0000_0000 => (b'3', b'7', 4),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "S-records can't hold data past address 0xffffffff",
+                ))
+            }
+        };
+        // The byte count also covers the address and checksum
+        if self.record_size + address_len + 1 > u8::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "S{} records hold at most {} bytes of data",
+                    data_type as char,
+                    u8::MAX as usize - address_len - 1
+                ),
+            ));
+        }
+
+        let header = self.header.as_bytes();
+        let header = &header[..header.len().min(u8::MAX as usize - 3)];
+        writeln!(writer, "{}", record(b'0', 0, 2, header))?;
+        let mut count = 0u64;
+        for (start, run) in &self.runs {
+            for (index, data) in run.chunks(self.record_size).enumerate() {
+                let address = start + (index * self.record_size) as u64;
+                writeln!(writer, "{}", record(data_type, address, address_len, data))?;
+                count += 1;
+            }
+        }
+        // Counts that don't fit either count record are left out
+        match count {
+            0..=0xffff => writeln!(writer, "{}", record(b'5', count, 2, &[]))?,
+            0x1_0000..=0xff_ffff => writeln!(writer, "{}", record(b'6', count, 3, &[]))?,
+            _ => {}
+        }
+        writeln!(writer, "{}", record(end_type, 0, address_len, &[]))
+    }
+}
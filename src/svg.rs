@@ -0,0 +1,109 @@
+use std::io::{self, Write};
+
+use crate::{
+    html::{class_color, escape, BACKGROUND_COLOR, DIM_COLOR},
+    render::{ByteClass, Renderer},
+};
+
+const FONT_SIZE: f64 = 14.0;
+/// Advance of a character in a monospace font, relative to its size
+const CHAR_WIDTH: f64 = FONT_SIZE * 0.6;
+const LINE_HEIGHT: f64 = FONT_SIZE * 1.4;
+const PADDING: f64 = FONT_SIZE;
+
+fn tspan(color: &str, text: &str) -> String {
+    format!("<tspan fill=\"{color}\">{}</tspan>", escape(text))
+}
+
+/// Renders the dump as an SVG image of a colored monospace grid
+pub struct Svg {
+    bytes_per_line: usize,
+    addr_width: usize,
+    uppercase: bool,
+    /// Character shown for each byte in the text column
+    glyph: fn(u8) -> String,
+    /// Text of every line, held back as the size of the image comes first
+    rows: Vec<String>,
+}
+
+impl Svg {
+    pub fn new(
+        bytes_per_line: usize,
+        addr_width: usize,
+        uppercase: bool,
+        glyph: fn(u8) -> String,
+    ) -> Self {
+        Svg {
+            bytes_per_line,
+            addr_width,
+            uppercase,
+            glyph,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Number of characters in a full line
+    fn line_len(&self) -> usize {
+        // Address with its prefix, then the hex and text columns with the separators between them
+        2 + self.addr_width + 3 + self.bytes_per_line * 3 - 1 + 3 + self.bytes_per_line
+    }
+}
+
+impl Renderer for Svg {
+    fn line(&mut self, _writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        let addr = match self.uppercase {
+            true => format!("0x{addr:0width$X}", width = self.addr_width),
+            false => format!("0x{addr:0width$x}", width = self.addr_width),
+        };
+        let hex: Vec<_> = bytes
+            .iter()
+            .map(|&byte| {
+                let hex = match self.uppercase {
+                    true => format!("{byte:02X}"),
+                    false => format!("{byte:02x}"),
+                };
+                tspan(class_color(ByteClass::of(byte)), &hex)
+            })
+            .collect();
+        let ascii: String = bytes
+            .iter()
+            .map(|&byte| tspan(class_color(ByteClass::of(byte)), &(self.glyph)(byte)))
+            .collect();
+        let extra_space = " ".repeat((self.bytes_per_line - bytes.len()) * 3);
+        let split = tspan(DIM_COLOR, "│");
+
+        self.rows.push(format!(
+            "{} {split} {}{extra_space} {split} {ascii}",
+            tspan(DIM_COLOR, &addr),
+            hex.join(" ")
+        ));
+        Ok(())
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        let width = self.line_len() as f64 * CHAR_WIDTH + 2.0 * PADDING;
+        let height = self.rows.len() as f64 * LINE_HEIGHT + 2.0 * PADDING;
+
+        writeln!(
+            writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.1}\" height=\"{height:.1}\" \
+             viewBox=\"0 0 {width:.1} {height:.1}\">"
+        )?;
+        writeln!(
+            writer,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{BACKGROUND_COLOR}\"/>"
+        )?;
+        writeln!(
+            writer,
+            "<g font-family=\"ui-monospace, Menlo, Consolas, monospace\" font-size=\"{FONT_SIZE}\" \
+             xml:space=\"preserve\">"
+        )?;
+        for (index, row) in self.rows.iter().enumerate() {
+            // Text is positioned by its baseline
+            let y = PADDING + (index as f64 + 0.75) * LINE_HEIGHT;
+            writeln!(writer, "<text x=\"{PADDING}\" y=\"{y:.1}\">{row}</text>")?;
+        }
+        writeln!(writer, "</g>")?;
+        writeln!(writer, "</svg>")
+    }
+}
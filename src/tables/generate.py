@@ -0,0 +1,36 @@
+#!/usr/bin/env python3
+"""Generates the double byte tables of codepage.rs by decoding every pair of bytes with the
+codecs of Python, leaving out the pairs that are private use characters.
+
+    python3 src/tables/generate.py
+"""
+
+from pathlib import Path
+
+TABLES = {"shift_jis": "cp932", "gbk": "gbk", "big5": "big5"}
+
+
+def code(pair, codec):
+    """Code point of the one character `pair` decodes to, or 0"""
+    try:
+        text = pair.decode(codec)
+    except UnicodeDecodeError:
+        return 0
+    if len(text) != 1 or 0xE000 <= ord(text) <= 0xF8FF or ord(text) > 0xFFFF:
+        return 0
+    return ord(text)
+
+
+def table(codec):
+    """A little endian u16 for every lead byte from 0x81 to 0xfe and trail byte from 0x40"""
+    return b"".join(
+        code(bytes([lead, trail]), codec).to_bytes(2, "little")
+        for lead in range(0x81, 0xFF)
+        for trail in range(0x40, 0xFF)
+    )
+
+
+if __name__ == "__main__":
+    directory = Path(__file__).parent
+    for name, codec in TABLES.items():
+        (directory / f"{name}.bin").write_bytes(table(codec))
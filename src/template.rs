@@ -0,0 +1,111 @@
+use std::io::{self, Write};
+
+use crate::render::{printable_char, Renderer};
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid template: {message}"),
+    )
+}
+
+enum Part {
+    Text(String),
+    /// Address of the line's first byte, in hex
+    Addr,
+    /// Address of the line's first byte, in decimal
+    Offset,
+    /// Hex bytes separated by spaces, padded to the width of a full line
+    Hex,
+    /// Hex bytes with nothing between them
+    Bytes,
+    /// Bytes as characters, with dots for anything unprintable
+    Ascii,
+    /// Number of bytes in the line
+    Len,
+}
+
+/// Renders each line by filling in the placeholders of a user's template, like
+/// `{addr}: {hex}  {ascii}`. Braces are written as `{{` and `}}`
+pub struct Template {
+    parts: Vec<Part>,
+    bytes_per_line: usize,
+    uppercase: bool,
+}
+
+impl Template {
+    /// Parses a template, which is filled in once for every line of the dump. A single
+    /// trailing newline is dropped, as each filled in template ends with one anyway
+    pub fn parse(text: &str, bytes_per_line: usize, uppercase: bool) -> io::Result<Self> {
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.next_if_eq(&'{').is_some() => literal.push('{'),
+                '}' if chars.next_if_eq(&'}').is_some() => literal.push('}'),
+                '{' => {
+                    let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    let part = match name.as_str() {
+                        "addr" => Part::Addr,
+                        "offset" => Part::Offset,
+                        "hex" => Part::Hex,
+                        "bytes" => Part::Bytes,
+                        "ascii" => Part::Ascii,
+                        "len" => Part::Len,
+                        _ => return Err(invalid(format!("unknown placeholder {{{name}}}"))),
+                    };
+                    if !literal.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut literal)));
+                    }
+                    parts.push(part);
+                }
+                '}' => return Err(invalid("unmatched }, write }} for a brace".to_string())),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Text(literal));
+        }
+
+        Ok(Template {
+            parts,
+            bytes_per_line,
+            uppercase,
+        })
+    }
+
+    fn hex(&self, bytes: &[u8]) -> String {
+        match self.uppercase {
+            true => hex::encode_upper(bytes),
+            false => hex::encode(bytes),
+        }
+    }
+}
+
+impl Renderer for Template {
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        let mut line = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => line += text,
+                Part::Addr => match self.uppercase {
+                    true => line += &format!("{addr:08X}"),
+                    false => line += &format!("{addr:08x}"),
+                },
+                Part::Offset => line += &addr.to_string(),
+                Part::Hex => {
+                    let hex: Vec<_> = bytes.iter().map(|byte| self.hex(&[*byte])).collect();
+                    let width = self.bytes_per_line * 3 - 1;
+                    line += &format!("{:<width$}", hex.join(" "));
+                }
+                Part::Bytes => line += &self.hex(bytes),
+                Part::Ascii => line.extend(bytes.iter().map(|&byte| printable_char(byte))),
+                Part::Len => line += &bytes.len().to_string(),
+            }
+        }
+        writeln!(writer, "{line}")
+    }
+}
@@ -0,0 +1,62 @@
+use std::env;
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod sys {
+    use std::os::raw::{c_int, c_ulong, c_ushort};
+
+    #[cfg(target_os = "linux")]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    #[cfg(target_os = "macos")]
+    const TIOCGWINSZ: c_ulong = 0x4008_7468;
+
+    /// Standard output, then standard error for when the output is piped into a pager
+    const FDS: [c_int; 2] = [1, 2];
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Winsize {
+        rows: c_ushort,
+        columns: c_ushort,
+        x_pixels: c_ushort,
+        y_pixels: c_ushort,
+    }
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    /// Columns and rows of the terminal
+    pub fn size() -> Option<(usize, usize)> {
+        FDS.iter().find_map(|&fd| {
+            let mut size = Winsize::default();
+            // SAFETY: TIOCGWINSZ only writes a winsize struct through the pointer
+            match unsafe { ioctl(fd, TIOCGWINSZ, &mut size as *mut Winsize) } {
+                0 if size.columns > 0 && size.rows > 0 => {
+                    Some((size.columns as usize, size.rows as usize))
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod sys {
+    pub fn size() -> Option<(usize, usize)> {
+        None
+    }
+}
+
+/// Number of columns of the terminal, or of $COLUMNS when it can't be asked
+pub fn width() -> Option<usize> {
+    sys::size()
+        .map(|(columns, _)| columns)
+        .or_else(|| env::var("COLUMNS").ok()?.parse().ok())
+}
+
+/// Number of rows of the terminal, or of $LINES when it can't be asked
+pub fn height() -> Option<usize> {
+    sys::size()
+        .map(|(_, rows)| rows)
+        .or_else(|| env::var("LINES").ok()?.parse().ok())
+}
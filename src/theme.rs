@@ -0,0 +1,391 @@
+use std::{
+    env, fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+};
+
+use colored::Color;
+
+use crate::{charset, render::ByteClass, util::parse_num};
+
+/// Names of the themes that come built in
+pub const BUILTIN_THEMES: [&str; 4] = ["default", "dark", "light", "mono"];
+
+/// Colors of the default style, where `None` leaves text in the color of the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub null: Option<Color>,
+    pub whitespace: Option<Color>,
+    pub printable: Option<Color>,
+    pub control: Option<Color>,
+    pub high: Option<Color>,
+    /// Color of addresses, separators and everything else that isn't a byte
+    pub dim: Option<Color>,
+}
+
+impl Theme {
+    fn builtin(name: &str) -> Option<Theme> {
+        let theme = match name {
+            "default" => Theme {
+                null: Some(Color::BrightBlack),
+                whitespace: Some(Color::Cyan),
+                printable: Some(Color::Green),
+                control: Some(Color::BrightRed),
+                high: Some(Color::Yellow),
+                dim: Some(Color::BrightBlack),
+            },
+            // Brighter colors, for dark palettes whose plain ones are hard to read
+            "dark" => Theme {
+                null: Some(Color::BrightBlack),
+                whitespace: Some(Color::BrightCyan),
+                printable: Some(Color::BrightGreen),
+                control: Some(Color::BrightRed),
+                high: Some(Color::BrightYellow),
+                dim: Some(Color::BrightBlack),
+            },
+            // Darker colors, without the yellow that vanishes on a light background
+            "light" => Theme {
+                null: Some(Color::BrightBlack),
+                whitespace: Some(Color::Blue),
+                printable: Some(Color::Green),
+                control: Some(Color::Red),
+                high: Some(Color::Magenta),
+                dim: Some(Color::BrightBlack),
+            },
+            "mono" => Theme {
+                null: None,
+                whitespace: None,
+                printable: None,
+                control: None,
+                high: None,
+                dim: None,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Color of the bytes of a class
+    pub fn class(&self, class: ByteClass) -> Option<Color> {
+        match class {
+            ByteClass::Null => self.null,
+            ByteClass::Whitespace => self.whitespace,
+            ByteClass::Printable => self.printable,
+            ByteClass::Control => self.control,
+            ByteClass::High => self.high,
+        }
+    }
+
+    /// Sets the color of a byte class or of `dim`, by name
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let color = parse_color(value)?;
+        match key {
+            "null" => self.null = color,
+            "whitespace" => self.whitespace = color,
+            "printable" => self.printable = color,
+            "control" => self.control = color,
+            "high" => self.high = color,
+            "dim" => self.dim = color,
+            _ => return Err(format!("Unknown color \"{key}\"")),
+        }
+        Ok(())
+    }
+}
+
+/// A color by name, like `bright-red` or `gray`, as `#rrggbb`, or `none` for no color
+pub fn parse_color(text: &str) -> Result<Option<Color>, String> {
+    if let Some(hex) = text.strip_prefix('#') {
+        let rgb = match hex.len() {
+            6 => u32::from_str_radix(hex, 16).ok(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("Invalid color \"{text}\", expected #rrggbb"))?;
+        return Ok(Some(Color::TrueColor {
+            r: (rgb >> 16) as u8,
+            g: (rgb >> 8) as u8,
+            b: rgb as u8,
+        }));
+    }
+    let color = match text.to_ascii_lowercase().replace('_', "-").as_str() {
+        "none" => return Ok(None),
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "gray" | "grey" | "bright-black" => Color::BrightBlack,
+        "bright-red" => Color::BrightRed,
+        "bright-green" => Color::BrightGreen,
+        "bright-yellow" => Color::BrightYellow,
+        "bright-blue" => Color::BrightBlue,
+        "bright-magenta" => Color::BrightMagenta,
+        "bright-cyan" => Color::BrightCyan,
+        "bright-white" => Color::BrightWhite,
+        _ => return Err(format!("Unknown color \"{text}\"")),
+    };
+    Ok(Some(color))
+}
+
+/// Foreground and background colors of a byte, either of which may be left as they are
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Paint {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+impl Paint {
+    pub fn fg(color: Option<Color>) -> Self {
+        Paint {
+            fg: color,
+            bg: None,
+        }
+    }
+
+    /// This paint with the colors of `other` wherever it has them
+    pub fn over(self, other: Paint) -> Self {
+        Paint {
+            fg: other.fg.or(self.fg),
+            bg: other.bg.or(self.bg),
+        }
+    }
+}
+
+/// Colors like `black/yellow`, where the one after a `/` is the background, and either
+/// may be left out
+pub fn parse_paint(text: &str) -> Result<Paint, String> {
+    let (fg, bg) = text.split_once('/').unwrap_or((text, ""));
+    let color = |text: &str| match text.trim() {
+        "" => Ok(None),
+        text => parse_color(text),
+    };
+    Ok(Paint {
+        fg: color(fg)?,
+        bg: color(bg)?,
+    })
+}
+
+/// Paint of the byte values picked by --highlight
+#[derive(Debug, Clone)]
+pub struct Highlights(pub [Option<Paint>; 256]);
+
+/// A list like `0x00=red,0xff=black/yellow` of byte values and their colors
+pub fn parse_highlights(text: &str) -> Result<Highlights, String> {
+    let mut highlights = Highlights([None; 256]);
+    for entry in text.split(',') {
+        let (byte, colors) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("Expected BYTE=COLOR, not \"{entry}\""))?;
+        let byte = parse_num(byte.trim())
+            .ok()
+            .filter(|&byte| byte <= 0xff)
+            .ok_or_else(|| format!("Invalid byte \"{}\"", byte.trim()))?;
+        highlights.0[byte] = Some(parse_paint(colors)?);
+    }
+    Ok(highlights)
+}
+
+/// Colors to change in a theme, as class and color names
+#[derive(Debug, Clone, Default)]
+pub struct ColorMap(pub Vec<(String, String)>);
+
+/// A list like `printable=green,null=gray` of colors to change, checked as it is parsed
+pub fn parse_color_map(text: &str) -> Result<ColorMap, String> {
+    let mut theme = Theme::builtin("default").expect("default is built in");
+    text.split(',')
+        .map(|entry| {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Expected CLASS=COLOR, not \"{entry}\""))?;
+            let (key, value) = (key.trim(), value.trim());
+            theme.set(key, value)?;
+            Ok((key.to_string(), value.to_string()))
+        })
+        .collect::<Result<_, String>>()
+        .map(ColorMap)
+}
+
+/// Glyphs of the text column changed by the `[glyphs]` section of the config
+#[derive(Debug, Clone, Default)]
+pub struct Glyphs {
+    /// Glyph of every ASCII character
+    pub ascii: Vec<Option<char>>,
+    /// Glyph of bytes the charset has no character for
+    pub unknown: Option<char>,
+}
+
+impl Glyphs {
+    /// Sets the glyph of `key`: null, tab, newline, return, space, control, high for the
+    /// bytes with no character, or a byte like `0x7f`. The glyph may be quoted, like `" "`
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let glyph = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        let mut chars = glyph.chars();
+        let glyph = match (chars.next(), chars.next()) {
+            (Some(c), None) if charset::width(glyph) == 1 => c,
+            _ => {
+                return Err(format!(
+                    "Glyph of {key} must be a single character, not \"{value}\""
+                ))
+            }
+        };
+        if self.ascii.is_empty() {
+            self.ascii = vec![None; 0x80];
+        }
+        let bytes = match key {
+            "null" => vec![0],
+            "tab" => vec![9],
+            "newline" => vec![10],
+            "return" => vec![13],
+            "space" => vec![32],
+            "control" => (1..0x20)
+                .chain([0x7f])
+                .filter(|byte| ![9, 10, 13].contains(byte))
+                .collect(),
+            "high" => {
+                self.unknown = Some(glyph);
+                Vec::new()
+            }
+            _ => match parse_num(key) {
+                Ok(byte) if byte < 0x80 => vec![byte],
+                _ => return Err(format!("Unknown glyph \"{key}\"")),
+            },
+        };
+        for byte in bytes {
+            self.ascii[byte] = Some(glyph);
+        }
+        Ok(())
+    }
+
+    /// Glyph of an ASCII character, if it was changed
+    pub fn of(&self, byte: u8) -> Option<char> {
+        self.ascii.get(byte as usize).copied().flatten()
+    }
+}
+
+/// Part of the config file a line is in
+enum Section {
+    Top,
+    Theme,
+    Glyphs,
+}
+
+/// Settings read from the config file
+#[derive(Debug, Default)]
+pub struct Config {
+    /// Theme used unless --theme picks another
+    pub theme: Option<String>,
+    /// Colors changed in whichever theme is used, like those of --color-map
+    colors: Vec<(String, String)>,
+    /// Colors of the `[theme NAME]` sections, in the order they were given
+    themes: Vec<(String, Vec<(String, String)>)>,
+    /// Glyphs of the `[glyphs]` section
+    pub glyphs: Glyphs,
+}
+
+impl Config {
+    /// Where the config file is, following the XDG base directories
+    pub fn path() -> Option<PathBuf> {
+        let dir = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("hex-thing").join("config"))
+    }
+
+    /// Reads the config file, which is fine to not have at all
+    pub fn load() -> io::Result<Config> {
+        let Some(path) = Config::path() else {
+            return Ok(Config::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => Config::parse(&text).map_err(|e| {
+                io::Error::new(ErrorKind::InvalidData, format!("{}: {e}", path.display()))
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses `key = value` lines, with a `[theme NAME]` line starting the colors of a theme
+    /// and a `[glyphs]` line the glyphs of the text column. Colors before the first section
+    /// change every theme. Lines starting with `#` are comments
+    fn parse(text: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        let mut current = Section::Top;
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                if section.trim() == "glyphs" {
+                    current = Section::Glyphs;
+                    continue;
+                }
+                let name = section
+                    .trim()
+                    .strip_prefix("theme")
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| {
+                        format!("Unknown section \"{section}\" on line {}", index + 1)
+                    })?;
+                config.themes.push((name.to_string(), Vec::new()));
+                current = Section::Theme;
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .ok_or_else(|| format!("Expected `key = value` on line {}", index + 1))?;
+            match current {
+                Section::Top if key == "theme" => config.theme = Some(value),
+                Section::Top => config.colors.push((key, value)),
+                Section::Theme => {
+                    if let Some((_, colors)) = config.themes.last_mut() {
+                        colors.push((key, value));
+                    }
+                }
+                Section::Glyphs => config
+                    .glyphs
+                    .set(&key, &value)
+                    .map_err(|e| format!("{e} on line {}", index + 1))?,
+            }
+        }
+        Ok(config)
+    }
+
+    /// The theme called `name`, built in or from a section of the config, with the colors
+    /// of `overrides` changed last. A section with the name of a built in theme changes
+    /// some of its colors
+    pub fn theme(&self, name: &str, overrides: &[(String, String)]) -> Result<Theme, String> {
+        let sections: Vec<_> = self
+            .themes
+            .iter()
+            .filter(|(section, _)| section == name)
+            .collect();
+        let mut theme = match Theme::builtin(name) {
+            Some(theme) => theme,
+            None if !sections.is_empty() => Theme::builtin("default").expect("default is built in"),
+            None => {
+                return Err(format!(
+                    "Unknown theme \"{name}\", expected one of {} or a [theme {name}] section in the config",
+                    BUILTIN_THEMES.join(", ")
+                ))
+            }
+        };
+        let colors = sections.iter().flat_map(|(_, colors)| colors);
+        for (key, value) in colors.chain(&self.colors).chain(overrides) {
+            theme.set(key, value)?;
+        }
+        Ok(theme)
+    }
+}
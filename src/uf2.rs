@@ -0,0 +1,104 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use crate::render::Renderer;
+
+const MAGIC_START: [u32; 2] = [0x0a32_4655, 0x9e5d_5157];
+const MAGIC_END: u32 = 0x0ab1_6f30;
+const BLOCK_SIZE: usize = 512;
+/// Bytes of data in each block, the flash page size bootloaders expect
+const PAYLOAD_SIZE: usize = 256;
+const FLAG_FAMILY_ID: u32 = 0x2000;
+
+/// Renders the bytes as UF2 blocks, for the USB drive of a bootloader to flash at `base_address`
+pub struct Uf2 {
+    base_address: u64,
+    family_id: Option<u32>,
+    /// Pages of data by their address, held back as every block holds the number of blocks
+    pages: BTreeMap<u64, [u8; PAYLOAD_SIZE]>,
+}
+
+impl Uf2 {
+    pub fn new(base_address: u64, family_id: Option<u32>) -> Self {
+        Uf2 {
+            base_address,
+            family_id,
+            pages: BTreeMap::new(),
+        }
+    }
+
+    fn push(&mut self, address: u64, bytes: &[u8]) {
+        for (index, byte) in bytes.iter().enumerate() {
+            let address = address + index as u64;
+            let page = address - address % PAYLOAD_SIZE as u64;
+            self.pages.entry(page).or_insert([0; PAYLOAD_SIZE])[(address - page) as usize] = *byte;
+        }
+    }
+}
+
+impl Renderer for Uf2 {
+    fn line(&mut self, _writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        self.push(self.base_address + addr as u64, bytes);
+        Ok(())
+    }
+
+    fn collapses_holes(&self) -> bool {
+        true
+    }
+
+    /// Gaps between records get no blocks, while the zeros of sparse file holes are written out
+    fn hole(
+        &mut self,
+        _writer: &mut dyn Write,
+        addr: usize,
+        len: usize,
+        is_gap: bool,
+    ) -> io::Result<()> {
+        if !is_gap {
+            self.push(self.base_address + addr as u64, &vec![0; len]);
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write, _end: usize) -> io::Result<()> {
+        if self
+            .pages
+            .last_key_value()
+            .is_some_and(|(page, _)| page + PAYLOAD_SIZE as u64 > u32::MAX as u64 + 1)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "UF2 can't hold data past address 0xffffffff",
+            ));
+        }
+
+        let count = self.pages.len() as u32;
+        let (flags, family_id) = match self.family_id {
+            Some(family_id) => (FLAG_FAMILY_ID, family_id),
+            None => (0, 0),
+        };
+        for (number, (address, data)) in self.pages.iter().enumerate() {
+            let header = [
+                MAGIC_START[0],
+                MAGIC_START[1],
+                flags,
+                *address as u32,
+                PAYLOAD_SIZE as u32,
+                number as u32,
+                count,
+                family_id,
+            ];
+            let mut block = Vec::with_capacity(BLOCK_SIZE);
+            for word in header {
+                block.extend_from_slice(&word.to_le_bytes());
+            }
+            block.extend_from_slice(data);
+            block.resize(BLOCK_SIZE - 4, 0);
+            block.extend_from_slice(&MAGIC_END.to_le_bytes());
+            writer.write_all(&block)?;
+        }
+        Ok(())
+    }
+}
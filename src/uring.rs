@@ -0,0 +1,419 @@
+//! Read ahead through io_uring, so the disk is busy while earlier blocks are rendered.
+//!
+//! This talks to the kernel through the raw `io_uring_setup`/`io_uring_enter`
+//! syscalls and keeps a handful of large reads in flight at consecutive offsets.
+
+use std::{
+    ffi::c_void,
+    fs::File,
+    io::{self, Read},
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        raw::{c_int, c_long},
+        unix::fs::FileExt,
+    },
+    ptr,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// Number of reads kept in flight
+const QUEUE_DEPTH: usize = 4;
+/// Size of each read
+const BLOCK_SIZE: usize = 256 * 1024;
+
+const SYS_IO_URING_SETUP: c_long = 425;
+const SYS_IO_URING_ENTER: c_long = 426;
+const IORING_OP_READ: u8 = 22;
+const IORING_ENTER_GETEVENTS: u32 = 1;
+const IORING_FEAT_SINGLE_MMAP: u32 = 1;
+const IORING_OFF_SQ_RING: i64 = 0;
+const IORING_OFF_CQ_RING: i64 = 0x800_0000;
+const IORING_OFF_SQES: i64 = 0x1000_0000;
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 1;
+const MAP_POPULATE: c_int = 0x8000;
+
+extern "C" {
+    fn syscall(number: c_long, ...) -> c_long;
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: i64,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct SqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    flags: u32,
+    dropped: u32,
+    array: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct CqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    overflow: u32,
+    cqes: u32,
+    flags: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct Params {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+
+#[repr(C)]
+struct Sqe {
+    opcode: u8,
+    flags: u8,
+    ioprio: u16,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    rw_flags: u32,
+    user_data: u64,
+    pad: [u64; 3],
+}
+
+#[repr(C)]
+struct Cqe {
+    user_data: u64,
+    res: i32,
+    flags: u32,
+}
+
+/// A shared memory region set up by the kernel for a ring
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl Mapping {
+    fn new(fd: &OwnedFd, len: usize, offset: i64) -> io::Result<Self> {
+        // SAFETY: maps a fresh region of the ring's file descriptor, nothing aliases it yet
+        let ptr = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ | PROT_WRITE,
+                MAP_SHARED | MAP_POPULATE,
+                fd.as_raw_fd(),
+                offset,
+            )
+        };
+        match ptr as isize {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(Mapping {
+                ptr: ptr as *mut u8,
+                len,
+            }),
+        }
+    }
+
+    /// # Safety
+    /// `offset` must point at a properly aligned value of type `T` inside the mapping
+    unsafe fn at<T>(&self, offset: u32) -> *mut T {
+        self.ptr.add(offset as usize) as *mut T
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: the region was mapped by `Mapping::new` and isn't referenced anymore
+        unsafe { munmap(self.ptr as *mut c_void, self.len) };
+    }
+}
+
+struct Ring {
+    fd: OwnedFd,
+    sq: Mapping,
+    /// Only set when the kernel can't share one mapping between both queues
+    cq: Option<Mapping>,
+    sqes: Mapping,
+    params: Params,
+}
+
+impl Ring {
+    fn new(entries: u32) -> io::Result<Self> {
+        let mut params = Params::default();
+        // SAFETY: io_uring_setup only writes to the params struct we pass it
+        let fd = unsafe { syscall(SYS_IO_URING_SETUP, entries, &mut params as *mut Params) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the syscall just returned a new file descriptor that nobody else owns
+        let fd = unsafe { OwnedFd::from_raw_fd(fd as c_int) };
+
+        let sq_len = params.sq_off.array as usize + params.sq_entries as usize * 4;
+        let cq_len =
+            params.cq_off.cqes as usize + params.cq_entries as usize * std::mem::size_of::<Cqe>();
+        let single_mmap = params.features & IORING_FEAT_SINGLE_MMAP != 0;
+
+        let sq = match single_mmap {
+            true => Mapping::new(&fd, sq_len.max(cq_len), IORING_OFF_SQ_RING)?,
+            false => Mapping::new(&fd, sq_len, IORING_OFF_SQ_RING)?,
+        };
+        let cq = match single_mmap {
+            true => None,
+            false => Some(Mapping::new(&fd, cq_len, IORING_OFF_CQ_RING)?),
+        };
+        let sqes = Mapping::new(
+            &fd,
+            params.sq_entries as usize * std::mem::size_of::<Sqe>(),
+            IORING_OFF_SQES,
+        )?;
+
+        Ok(Ring {
+            fd,
+            sq,
+            cq,
+            sqes,
+            params,
+        })
+    }
+
+    fn cq(&self) -> &Mapping {
+        self.cq.as_ref().unwrap_or(&self.sq)
+    }
+
+    fn atomic(mapping: &Mapping, offset: u32) -> &AtomicU32 {
+        // SAFETY: the kernel reports offsets of aligned u32 ring fields inside the mapping
+        unsafe { &*mapping.at::<AtomicU32>(offset) }
+    }
+
+    fn enter(&self, submit: u32, wait: u32, flags: u32) -> io::Result<()> {
+        // SAFETY: io_uring_enter only reads the rings shared with the kernel
+        let result = unsafe {
+            syscall(
+                SYS_IO_URING_ENTER,
+                self.fd.as_raw_fd(),
+                submit,
+                wait,
+                flags,
+                ptr::null::<c_void>(),
+                0usize,
+            )
+        };
+        match result {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Queues a read of `buf.len()` bytes at `offset` and submits it
+    fn submit_read(&self, file: &File, buf: &mut [u8], offset: u64, tag: u64) -> io::Result<()> {
+        let off = &self.params.sq_off;
+        let tail = Self::atomic(&self.sq, off.tail).load(Ordering::Acquire);
+        // SAFETY: ring_mask is an aligned u32 inside the submission ring
+        let index = tail & unsafe { *self.sq.at::<u32>(off.ring_mask) };
+
+        // SAFETY: `index` is masked to the number of entries in both arrays
+        unsafe {
+            self.sqes
+                .at::<Sqe>(index * std::mem::size_of::<Sqe>() as u32)
+                .write(Sqe {
+                    opcode: IORING_OP_READ,
+                    flags: 0,
+                    ioprio: 0,
+                    fd: file.as_raw_fd(),
+                    off: offset,
+                    addr: buf.as_mut_ptr() as u64,
+                    len: buf.len() as u32,
+                    rw_flags: 0,
+                    user_data: tag,
+                    pad: [0; 3],
+                });
+            *self.sq.at::<u32>(off.array + index * 4) = index;
+        }
+        Self::atomic(&self.sq, off.tail).store(tail.wrapping_add(1), Ordering::Release);
+
+        self.enter(1, 0, 0)
+    }
+
+    /// Waits for the next completion, returning its tag and result. A wait interrupted by a
+    /// signal is started over
+    fn wait_completion(&self) -> io::Result<(u64, i32)> {
+        let off = &self.params.cq_off;
+        let cq = self.cq();
+        loop {
+            let head = Self::atomic(cq, off.head).load(Ordering::Acquire);
+            let tail = Self::atomic(cq, off.tail).load(Ordering::Acquire);
+            if head != tail {
+                // SAFETY: ring_mask is an aligned u32 inside the completion ring
+                let mask = unsafe { *cq.at::<u32>(off.ring_mask) };
+                let index = head & mask;
+                // SAFETY: the kernel has published this entry, and `index` is masked
+                let cqe = unsafe {
+                    cq.at::<Cqe>(off.cqes + index * std::mem::size_of::<Cqe>() as u32)
+                        .read()
+                };
+                Self::atomic(cq, off.head).store(head.wrapping_add(1), Ordering::Release);
+                return Ok((cqe.user_data, cqe.res));
+            }
+            match self.enter(0, 1, IORING_ENTER_GETEVENTS) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                result => result?,
+            }
+        }
+    }
+}
+
+struct Slot {
+    buf: Vec<u8>,
+    offset: u64,
+    /// Bytes read into `buf`, once the read has completed
+    filled: Option<usize>,
+    in_flight: bool,
+}
+
+/// Sequential reader over a file that keeps several reads ahead in flight
+pub struct UringReader {
+    ring: Ring,
+    file: File,
+    slots: Vec<Slot>,
+    /// Slot currently being consumed, and how far into it
+    current: usize,
+    consumed: usize,
+    /// Offset of the next read to submit
+    next_offset: u64,
+    size: u64,
+}
+
+impl UringReader {
+    /// Starts reading `file` at `offset`
+    pub fn new(file: File, offset: u64) -> io::Result<Self> {
+        let size = file.metadata()?.len();
+        let mut reader = UringReader {
+            ring: Ring::new(QUEUE_DEPTH as u32)?,
+            file,
+            slots: (0..QUEUE_DEPTH)
+                .map(|_| Slot {
+                    buf: vec![0; BLOCK_SIZE],
+                    offset: 0,
+                    filled: None,
+                    in_flight: false,
+                })
+                .collect(),
+            current: 0,
+            consumed: 0,
+            next_offset: offset,
+            size,
+        };
+        for index in 0..QUEUE_DEPTH {
+            reader.submit(index)?;
+        }
+        Ok(reader)
+    }
+
+    /// Queues the next block into slot `index`, unless the end of the file was reached
+    fn submit(&mut self, index: usize) -> io::Result<()> {
+        let slot = &mut self.slots[index];
+        slot.offset = self.next_offset;
+        slot.filled = None;
+        if self.next_offset >= self.size {
+            slot.filled = Some(0);
+            return Ok(());
+        }
+        self.ring
+            .submit_read(&self.file, &mut slot.buf, slot.offset, index as u64)?;
+        slot.in_flight = true;
+        self.next_offset += BLOCK_SIZE as u64;
+        Ok(())
+    }
+
+    /// Blocks until the read into slot `index` has completed
+    fn wait(&mut self, index: usize) -> io::Result<()> {
+        while self.slots[index].in_flight {
+            let (tag, res) = self.ring.wait_completion()?;
+            let slot = &mut self.slots[tag as usize];
+            slot.in_flight = false;
+            if res < 0 {
+                return Err(io::Error::from_raw_os_error(-res));
+            }
+
+            // Short reads only happen at the end of a regular file, but finish them just in case
+            let expected = (self.size - slot.offset).min(BLOCK_SIZE as u64) as usize;
+            let res = res as usize;
+            if res < expected {
+                self.file
+                    .read_exact_at(&mut slot.buf[res..expected], slot.offset + res as u64)?;
+            }
+            slot.filled = Some(expected);
+        }
+        Ok(())
+    }
+}
+
+impl Read for UringReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            self.wait(self.current)?;
+            let slot = &self.slots[self.current];
+            let filled = slot.filled.unwrap_or_default();
+
+            if filled == 0 {
+                // End of file
+                return Ok(0);
+            }
+            if self.consumed < filled {
+                let len = buf.len().min(filled - self.consumed);
+                buf[..len].copy_from_slice(&slot.buf[self.consumed..self.consumed + len]);
+                self.consumed += len;
+                return Ok(len);
+            }
+
+            // This block is used up, so reuse its buffer for the next one
+            self.submit(self.current)?;
+            self.current = (self.current + 1) % QUEUE_DEPTH;
+            self.consumed = 0;
+        }
+    }
+}
+
+impl Drop for UringReader {
+    fn drop(&mut self) {
+        // The kernel may still be writing into the buffers, so let pending reads finish first
+        while self.slots.iter().any(|slot| slot.in_flight) {
+            match self.ring.wait_completion() {
+                Ok((tag, _)) => self.slots[tag as usize].in_flight = false,
+                // Without knowing when they do, the buffers are leaked rather than freed
+                Err(_) => {
+                    for slot in self.slots.iter_mut().filter(|slot| slot.in_flight) {
+                        std::mem::forget(std::mem::take(&mut slot.buf));
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,62 @@
+use std::io::{self, Read, Write};
+
 pub fn parse_num(input: &str) -> Result<usize, std::num::ParseIntError> {
-    if input.starts_with("0x") {
-        usize::from_str_radix(&input[2..], 16)
-    } else {
-        usize::from_str_radix(input, 10)
+    match input.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => input.parse(),
+    }
+}
+
+/// Reads into `buf` until it is full or the reader hits EOF, returning the number of bytes read.
+/// Unlike a single `read` call this never returns a short count in the middle of a stream.
+pub fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Writer passing on the first lines written to it, after which writing fails with a
+/// broken pipe, as if the output had gone to `head`
+pub struct LineLimit<W> {
+    inner: W,
+    left: usize,
+}
+
+impl<W: Write> LineLimit<W> {
+    pub fn new(inner: W, lines: usize) -> Self {
+        LineLimit { inner, left: lines }
+    }
+}
+
+impl<W: Write> Write for LineLimit<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.left == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "The limit of --lines was reached",
+            ));
+        }
+        // Only up to the end of the last line allowed, so the next write fails
+        let end = buf
+            .iter()
+            .enumerate()
+            .filter(|&(_, &byte)| byte == b'\n')
+            .nth(self.left - 1)
+            .map_or(buf.len(), |(index, _)| index + 1);
+        let written = self.inner.write(&buf[..end])?;
+        self.left -= buf[..written].iter().filter(|&&byte| byte == b'\n').count();
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
     }
 }
 
@@ -24,3 +78,44 @@ impl<T> ApplyIf for T {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reader that returns a byte per `read`, and is interrupted before every other one
+    struct ByteReads<'a> {
+        data: &'a [u8],
+        interrupt: bool,
+    }
+
+    impl Read for ByteReads<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            let Some((&byte, rest)) = self.data.split_first().filter(|_| !buf.is_empty()) else {
+                return Ok(0);
+            };
+            buf[0] = byte;
+            self.data = rest;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn read_full_fills_buffer_from_reads_of_a_byte() {
+        let data: Vec<u8> = (0..40).collect();
+        let mut reader = ByteReads {
+            data: &data,
+            interrupt: false,
+        };
+        let mut buf = [0; 16];
+        for line in data.chunks(16) {
+            assert_eq!(read_full(&mut reader, &mut buf).unwrap(), line.len());
+            assert_eq!(&buf[..line.len()], line);
+        }
+        assert_eq!(read_full(&mut reader, &mut buf).unwrap(), 0);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{html::class_color, png::Bitmap, render::ByteClass};
+
+/// What the image shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Plot {
+    /// The bytes themselves, colored by their class
+    Byteview,
+    /// How often each byte follows each other byte, as a 256×256 heatmap with the first byte
+    /// of a pair along the x axis. Encrypted and compressed data shows up as noise all over it
+    Digraph,
+}
+
+/// Order in which the bytes fill the image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Layout {
+    /// Row by row, a pixel for each byte
+    Linear,
+    /// Along a Hilbert curve through a square, so bytes near each other in the input stay
+    /// near each other in the image
+    Hilbert,
+}
+
+/// Color of the pixels past the end of the data
+const PADDING_COLOR: [u8; 3] = [0, 0, 0];
+/// Most rows of a linear byteview, past which each pixel shows the average of more bytes
+pub const MAX_ROWS: usize = 4096;
+
+/// Parses a `#rrggbb` color
+fn rgb(color: &str) -> [u8; 3] {
+    let mut rgb = [0; 3];
+    hex::decode_to_slice(&color[1..], &mut rgb).expect("colors are written as #rrggbb");
+    rgb
+}
+
+/// Color of the class of `byte`, brighter for higher values within the class
+fn byte_color(byte: u8) -> [u8; 3] {
+    let brightness = 0.6 + 0.4 * byte as f64 / 255.0;
+    rgb(class_color(ByteClass::of(byte))).map(|channel| (channel as f64 * brightness) as u8)
+}
+
+/// Colors of the bytes that go in a pixel, added up
+#[derive(Debug, Clone, Copy, Default)]
+struct Pixel {
+    sum: [u64; 3],
+    count: u64,
+}
+
+impl Pixel {
+    fn add(&mut self, color: [u8; 3]) {
+        for (sum, channel) in self.sum.iter_mut().zip(color) {
+            *sum += channel as u64;
+        }
+        self.count += 1;
+    }
+
+    fn merge(self, other: Pixel) -> Pixel {
+        Pixel {
+            sum: [0, 1, 2].map(|channel| self.sum[channel] + other.sum[channel]),
+            count: self.count + other.count,
+        }
+    }
+
+    /// Average color of the bytes in the pixel
+    fn color(self) -> [u8; 3] {
+        self.sum.map(|sum| (sum / self.count) as u8)
+    }
+}
+
+/// Position of the point at `distance` along a Hilbert curve through a square of `side` pixels
+fn hilbert_point(side: usize, mut distance: usize) -> (usize, usize) {
+    let (mut x, mut y) = (0, 0);
+    let mut size = 1;
+    while size < side {
+        let rx = 1 & (distance / 2);
+        let ry = 1 & (distance ^ rx);
+        // Rotate the quadrant so the curve connects to the previous one
+        if ry == 0 {
+            if rx == 1 {
+                x = size - 1 - x;
+                y = size - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        x += size * rx;
+        y += size * ry;
+        distance /= 4;
+        size *= 2;
+    }
+    (x, y)
+}
+
+/// A picture of the bytes laid out by `layout`, drawn as they are read.
+/// Linear pictures have a pixel for each byte, filling rows of `width` pixels, while Hilbert
+/// pictures are squares `width` pixels wide (rounded up to a power of two). Once the bytes
+/// don't fit, each pixel has the average color of as many of them as it takes
+pub struct Byteview {
+    layout: Layout,
+    width: usize,
+    /// Most pixels the picture has
+    capacity: usize,
+    /// Bytes that go in each pixel
+    per_pixel: u64,
+    pixels: Vec<Pixel>,
+    /// The pixel the next bytes go in
+    next: Pixel,
+}
+
+impl Byteview {
+    /// A picture of `size` bytes if that is known, or one that shrinks the bytes it already
+    /// has into fewer pixels whenever it runs out of them
+    pub fn new(width: usize, layout: Layout, size: Option<u64>) -> Self {
+        let (width, capacity) = match layout {
+            Layout::Linear => (width, width * MAX_ROWS),
+            Layout::Hilbert => {
+                let side = width.next_power_of_two();
+                (side, side * side)
+            }
+        };
+        Byteview {
+            layout,
+            width,
+            capacity,
+            per_pixel: size.map_or(1, |size| size.div_ceil(capacity as u64).max(1)),
+            pixels: Vec::new(),
+            next: Pixel::default(),
+        }
+    }
+
+    pub fn add(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.next.add(byte_color(byte));
+            if self.next.count < self.per_pixel {
+                continue;
+            }
+            if self.pixels.len() == self.capacity {
+                // Every two pixels become one, which leaves room for as many again
+                self.pixels = self
+                    .pixels
+                    .chunks(2)
+                    .map(|pair| {
+                        pair.iter()
+                            .fold(Pixel::default(), |sum, &pixel| sum.merge(pixel))
+                    })
+                    .collect();
+                self.per_pixel *= 2;
+                continue;
+            }
+            self.pixels.push(std::mem::take(&mut self.next));
+        }
+    }
+
+    pub fn finish(mut self) -> Bitmap {
+        if self.next.count > 0 {
+            self.pixels.push(self.next);
+        }
+        match self.layout {
+            Layout::Linear => {
+                let height = self.pixels.len().div_ceil(self.width).max(1);
+                let mut bitmap = Bitmap::new(self.width, height, PADDING_COLOR);
+                for (index, pixel) in self.pixels.iter().enumerate() {
+                    bitmap.set(index % self.width, index / self.width, pixel.color());
+                }
+                bitmap
+            }
+            Layout::Hilbert => {
+                let mut bitmap = Bitmap::new(self.width, self.width, PADDING_COLOR);
+                for (distance, pixel) in self.pixels.iter().enumerate() {
+                    let (x, y) = hilbert_point(self.width, distance);
+                    bitmap.set(x, y, pixel.color());
+                }
+                bitmap
+            }
+        }
+    }
+}
+
+/// Color of a heatmap cell, from dark red for a `heat` near 0 to white for 1
+fn heat_color(heat: f64) -> [u8; 3] {
+    [heat.sqrt(), heat, heat * heat].map(|channel| (channel * 255.0) as u8)
+}
+
+/// A 256×256 heatmap of how often every pair of consecutive bytes occurs, counted as they
+/// are read
+pub struct Digraph {
+    counts: Vec<usize>,
+    /// The last byte read, the first of a pair with the next one
+    last: Option<u8>,
+}
+
+impl Digraph {
+    pub fn new() -> Self {
+        Digraph {
+            counts: vec![0; 256 * 256],
+            last: None,
+        }
+    }
+
+    pub fn add(&mut self, data: &[u8]) {
+        for &byte in data {
+            if let Some(last) = self.last {
+                self.counts[last as usize + byte as usize * 256] += 1;
+            }
+            self.last = Some(byte);
+        }
+    }
+
+    pub fn finish(self) -> Bitmap {
+        // Counts span orders of magnitude, so the heat is logarithmic
+        let max = self.counts.iter().copied().max().unwrap_or(0);
+        let mut bitmap = Bitmap::new(256, 256, PADDING_COLOR);
+        for (index, &count) in self.counts.iter().enumerate() {
+            if count > 0 {
+                let heat = (count as f64).ln_1p() / (max as f64).ln_1p();
+                bitmap.set(index % 256, index / 256, heat_color(heat));
+            }
+        }
+        bitmap
+    }
+}
+
+/// Either plot, drawn as the input is read so it never has to be held whole
+pub enum Picture {
+    Byteview(Byteview),
+    Digraph(Digraph),
+}
+
+impl Picture {
+    /// A picture of `plot` for an input of `size` bytes, if that is known
+    pub fn new(plot: Plot, width: usize, layout: Layout, size: Option<u64>) -> Self {
+        match plot {
+            Plot::Byteview => Picture::Byteview(Byteview::new(width, layout, size)),
+            Plot::Digraph => Picture::Digraph(Digraph::new()),
+        }
+    }
+
+    pub fn add(&mut self, data: &[u8]) {
+        match self {
+            Picture::Byteview(byteview) => byteview.add(data),
+            Picture::Digraph(digraph) => digraph.add(data),
+        }
+    }
+
+    pub fn finish(self) -> Bitmap {
+        match self {
+            Picture::Byteview(byteview) => byteview.finish(),
+            Picture::Digraph(digraph) => digraph.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_byteview_keeps_to_its_rows() {
+        let data = vec![0x41; 4 * MAX_ROWS * 3 + 5];
+        for size in [None, Some(data.len() as u64)] {
+            let mut byteview = Byteview::new(4, Layout::Linear, size);
+            for chunk in data.chunks(1000) {
+                byteview.add(chunk);
+            }
+            let bitmap = byteview.finish();
+            assert_eq!(bitmap.width, 4);
+            assert!(bitmap.height <= MAX_ROWS && bitmap.height > MAX_ROWS / 2);
+            assert_eq!(bitmap.pixels[0], byte_color(0x41));
+        }
+    }
+
+    #[test]
+    fn small_byteview_has_a_pixel_for_each_byte() {
+        let mut byteview = Byteview::new(4, Layout::Linear, None);
+        byteview.add(&[0, 0x41, 0xff, b' ', 7]);
+        let bitmap = byteview.finish();
+        assert_eq!((bitmap.width, bitmap.height), (4, 2));
+        assert_eq!(bitmap.pixels[2], byte_color(0xff));
+        assert_eq!(bitmap.pixels[4], byte_color(7));
+        assert_eq!(bitmap.pixels[5], PADDING_COLOR);
+    }
+
+    #[test]
+    fn digraph_counts_pairs_across_reads() {
+        let mut digraph = Digraph::new();
+        digraph.add(b"a");
+        digraph.add(b"b");
+        assert_eq!(digraph.counts[b'a' as usize + b'b' as usize * 256], 1);
+    }
+}
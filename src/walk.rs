@@ -0,0 +1,88 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Collects every file below `dir` in sorted order, keeping only those matching
+/// one of `globs` (if any are given). Symlinked directories aren't followed.
+pub fn files(dir: &Path, globs: &[String]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    walk(dir, dir, globs, &mut files)?;
+    Ok(files)
+}
+
+fn walk(root: &Path, dir: &Path, globs: &[String], files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        if fs::symlink_metadata(&path)?.is_dir() {
+            walk(root, &path, globs, files)?;
+        } else if path.is_file() && matches_any(root, &path, globs) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Patterns with a slash are matched against the path relative to `root`,
+/// others against the file name alone
+fn matches_any(root: &Path, path: &Path, globs: &[String]) -> bool {
+    if globs.is_empty() {
+        return true;
+    }
+    let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    globs.iter().any(|glob| match glob.contains('/') {
+        true => glob_match(glob.as_bytes(), relative.as_bytes()),
+        false => glob_match(glob.as_bytes(), name.as_bytes()),
+    })
+}
+
+/// Matches shell style wildcards: `*`, `?` and `[...]` character classes
+/// (with `!` or `^` negation and `a-z` ranges)
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') => (0..=text.len()).any(|skip| glob_match(&pattern[1..], &text[skip..])),
+        Some(b'?') => !text.is_empty() && glob_match(&pattern[1..], &text[1..]),
+        Some(b'[') => match (text.first(), class_match(&pattern[1..], text.first())) {
+            (Some(_), Some((true, rest))) => glob_match(rest, &text[1..]),
+            // An unterminated class is matched literally
+            (Some(b'['), None) => glob_match(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+        Some(&literal) => text.first() == Some(&literal) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Matches `byte` against the class at the start of `pattern` (just after the `[`),
+/// returning whether it matched and the pattern following the class
+fn class_match<'a>(pattern: &'a [u8], byte: Option<&u8>) -> Option<(bool, &'a [u8])> {
+    let byte = *byte?;
+    let (negated, mut class) = match pattern.first() {
+        Some(b'!' | b'^') => (true, &pattern[1..]),
+        _ => (false, pattern),
+    };
+
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        match class {
+            [] => return None,
+            [b']', rest @ ..] if !first => return Some((matched != negated, rest)),
+            [low, b'-', high, rest @ ..] if *high != b']' => {
+                matched |= (*low..=*high).contains(&byte);
+                class = rest;
+            }
+            [single, rest @ ..] => {
+                matched |= *single == byte;
+                class = rest;
+            }
+        }
+        first = false;
+    }
+}
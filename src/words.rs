@@ -0,0 +1,156 @@
+/// Integer type the bytes of a line are read as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Word {
+    U16,
+    U32,
+    U64,
+}
+
+/// Floating point type the bytes of a line are read as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Float {
+    F32,
+    F64,
+}
+
+/// Order of the bytes in a word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+impl Word {
+    /// Number of bytes in a word
+    pub fn size(self) -> usize {
+        match self {
+            Word::U16 => 2,
+            Word::U32 => 4,
+            Word::U64 => 8,
+        }
+    }
+
+    /// Number of characters of the longest word
+    fn width(self, signed: bool) -> usize {
+        match (self, signed) {
+            (Word::U16, false) => u16::MAX.to_string().len(),
+            (Word::U32, false) => u32::MAX.to_string().len(),
+            (Word::U64, false) => u64::MAX.to_string().len(),
+            (Word::U16, true) => i16::MIN.to_string().len(),
+            (Word::U32, true) => i32::MIN.to_string().len(),
+            (Word::U64, true) => i64::MIN.to_string().len(),
+        }
+    }
+
+    /// The word as a number, in two's complement when `signed`
+    fn format(self, bytes: &[u8], endian: Endian, signed: bool) -> String {
+        let value = read(bytes, endian);
+        match signed {
+            true => {
+                // Shift the sign bit of the word to the top, and back with sign extension
+                let shift = 64 - 8 * self.size() as u32;
+                (((value << shift) as i64) >> shift).to_string()
+            }
+            false => value.to_string(),
+        }
+    }
+}
+
+/// Value of the word in `bytes`, which are at most 8 long
+fn read(bytes: &[u8], endian: Endian) -> u64 {
+    let mut buf = [0u8; 8];
+    match endian {
+        Endian::Little => {
+            buf[..bytes.len()].copy_from_slice(bytes);
+            u64::from_le_bytes(buf)
+        }
+        Endian::Big => {
+            buf[8 - bytes.len()..].copy_from_slice(bytes);
+            u64::from_be_bytes(buf)
+        }
+    }
+}
+
+/// `values` right aligned to `width`, in a column wide enough for `count` of them
+fn align(values: Vec<String>, width: usize, count: usize) -> String {
+    let values: Vec<_> = values
+        .iter()
+        .map(|value| format!("{value:>width$}"))
+        .collect();
+    let column_width = (count * (width + 1)).saturating_sub(1);
+    format!("{:<column_width$}", values.join(" "))
+}
+
+/// The words of `bytes` as right aligned numbers, in a column wide enough for
+/// `bytes_per_line` bytes. Bytes after the last full word are left out
+pub fn column(
+    bytes: &[u8],
+    bytes_per_line: usize,
+    word: Word,
+    endian: Endian,
+    signed: bool,
+) -> String {
+    let values = bytes
+        .chunks_exact(word.size())
+        .map(|bytes| word.format(bytes, endian, signed))
+        .collect();
+    align(values, word.width(signed), bytes_per_line / word.size())
+}
+
+impl Float {
+    pub fn size(self) -> usize {
+        match self {
+            Float::F32 => 4,
+            Float::F64 => 8,
+        }
+    }
+
+    /// Number of characters of the longest value
+    fn width(self) -> usize {
+        match self {
+            Float::F32 => 16,
+            Float::F64 => 24,
+        }
+    }
+
+    /// Number of significant digits the type holds
+    fn precision(self) -> i32 {
+        match self {
+            Float::F32 => f32::DIGITS as i32,
+            Float::F64 => f64::DIGITS as i32,
+        }
+    }
+
+    /// The value, written plainly when it is of a reasonable magnitude and in scientific
+    /// notation otherwise, like `%g` with the shortest digits that read back the same value
+    fn format(self, bytes: &[u8], endian: Endian) -> String {
+        let bits = read(bytes, endian);
+        let (plain, scientific, value) = match self {
+            Float::F32 => {
+                let value = f32::from_bits(bits as u32);
+                (value.to_string(), format!("{value:e}"), value as f64)
+            }
+            Float::F64 => {
+                let value = f64::from_bits(bits);
+                (value.to_string(), format!("{value:e}"), value)
+            }
+        };
+        if value == 0.0 || !value.is_finite() {
+            return plain;
+        }
+        let exponent = value.abs().log10().floor() as i32;
+        match (-5..self.precision()).contains(&exponent) {
+            true => plain,
+            false => scientific,
+        }
+    }
+}
+
+/// The floats in `bytes`, in a column wide enough for `bytes_per_line` bytes
+pub fn float_column(bytes: &[u8], bytes_per_line: usize, float: Float, endian: Endian) -> String {
+    let values = bytes
+        .chunks_exact(float.size())
+        .map(|bytes| float.format(bytes, endian))
+        .collect();
+    align(values, float.width(), bytes_per_line / float.size())
+}
@@ -0,0 +1,40 @@
+use std::io::{self, Write};
+
+use crate::render::{printable_char, Renderer};
+
+/// Bytes per group of hex digits, as with xxd's default `-g 2`
+const GROUP_SIZE: usize = 2;
+
+/// Renders lines exactly like `xxd`
+pub struct Xxd {
+    bytes_per_line: usize,
+    uppercase: bool,
+}
+
+impl Xxd {
+    pub fn new(bytes_per_line: usize, uppercase: bool) -> Self {
+        Xxd {
+            bytes_per_line,
+            uppercase,
+        }
+    }
+}
+
+impl Renderer for Xxd {
+    fn line(&mut self, writer: &mut dyn Write, addr: usize, bytes: &[u8]) -> io::Result<()> {
+        let hex = bytes
+            .chunks(GROUP_SIZE)
+            .map(|group| match self.uppercase {
+                true => hex::encode_upper(group),
+                false => hex::encode(group),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        // Short lines are padded so their text lines up with that of full lines
+        let width = self.bytes_per_line * 2 + self.bytes_per_line.div_ceil(GROUP_SIZE) - 1;
+        let ascii: String = bytes.iter().map(|&byte| printable_char(byte)).collect();
+
+        // xxd keeps the offset lowercase even with -u
+        writeln!(writer, "{addr:08x}: {hex:<width$}  {ascii}")
+    }
+}
@@ -0,0 +1,837 @@
+use std::io::{self, BufRead, Read};
+
+use crate::checksum::Xxh64;
+
+const MAGIC: u32 = 0xfd2f_b528;
+/// Skippable frames have any magic number from this one up to 15 more
+const SKIPPABLE_MAGIC: u32 = 0x184d_2a50;
+const MAX_BLOCK_SIZE: usize = 128 * 1024;
+/// Largest window accepted, so a corrupt header can't claim one of terabytes
+const MAX_WINDOW_SIZE: u64 = 1 << 31;
+const MAX_HUFFMAN_BITS: u32 = 11;
+
+const LL_BASE: [u32; 36] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 20, 22, 24, 28, 32, 40, 48, 64,
+    128, 256, 512, 1024, 2048, 4096, 8192, 16384, 32768, 65536,
+];
+const LL_BITS: [u32; 36] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11,
+    12, 13, 14, 15, 16,
+];
+const ML_BASE: [u32; 53] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27,
+    28, 29, 30, 31, 32, 33, 34, 35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027,
+    2051, 4099, 8195, 16387, 32771, 65539,
+];
+const ML_BITS: [u32; 53] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+];
+
+/// Distributions of the predefined tables of literal lengths, offsets and match lengths
+const LL_DEFAULT: [i32; 36] = [
+    4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1,
+    -1, -1, -1, -1,
+];
+const OF_DEFAULT: [i32; 29] = [
+    1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1,
+];
+const ML_DEFAULT: [i32; 53] = [
+    1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1, -1, -1,
+];
+
+/// Accuracy of the predefined tables, the largest a table may have and its largest symbol,
+/// for the literal lengths, offsets and match lengths
+const LL_TABLE: (u32, u32, usize) = (6, 9, 35);
+const OF_TABLE: (u32, u32, usize) = (5, 8, 31);
+const ML_TABLE: (u32, u32, usize) = (6, 9, 52);
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid zstd data: {message}"),
+    )
+}
+
+fn highest_bit(value: u32) -> u32 {
+    31 - value.leading_zeros()
+}
+
+/// `len` bytes of `data` from `start`, unless it ends before
+fn slice(data: &[u8], start: usize, len: usize) -> io::Result<&[u8]> {
+    data.get(start..start + len)
+        .ok_or_else(|| invalid("block ends early"))
+}
+
+/// Reads a byte slice least significant bit first, as table descriptions are packed
+struct ForwardBits<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl ForwardBits<'_> {
+    /// Reads `count` bits, with zeros past the end for `bytes` to fail on
+    fn read(&mut self, count: u32) -> u32 {
+        let mut value = 0;
+        for index in 0..count {
+            let byte = self.data.get(self.pos / 8).copied().unwrap_or(0);
+            value |= (((byte >> (self.pos % 8)) & 1) as u32) << index;
+            self.pos += 1;
+        }
+        value
+    }
+
+    /// Bytes read so far, failing if that is more than there are
+    fn bytes(&self) -> io::Result<usize> {
+        match self.pos.div_ceil(8) {
+            len if len <= self.data.len() => Ok(len),
+            _ => Err(invalid("table description ends early")),
+        }
+    }
+}
+
+/// Reads a byte slice from its end back to its start, as zstd writes entropy coded streams
+struct BackwardBits<'a> {
+    data: &'a [u8],
+    /// Bits left to read, going negative once reads run past the start
+    left: isize,
+}
+
+impl<'a> BackwardBits<'a> {
+    fn new(data: &'a [u8]) -> io::Result<Self> {
+        match data.last() {
+            // The stream is padded with zeros and a one up to the end of its last byte
+            Some(&last) if last != 0 => Ok(BackwardBits {
+                data,
+                left: ((data.len() - 1) * 8) as isize + highest_bit(last as u32) as isize,
+            }),
+            _ => Err(invalid("bad bitstream end")),
+        }
+    }
+
+    /// Reads `count` bits, with zeros for those past the start
+    fn read(&mut self, count: u32) -> u64 {
+        if count == 0 {
+            return 0;
+        }
+        let start = self.left - count as isize;
+        self.left = start;
+        let low = start.max(0) as usize;
+        let high = start + count as isize;
+        if high <= 0 {
+            return 0;
+        }
+        let len = high as usize - low;
+        let mut window = [0u8; 8];
+        let end = (low / 8 + 8).min(self.data.len());
+        window[..end - low / 8].copy_from_slice(&self.data[low / 8..end]);
+        let value = (u64::from_le_bytes(window) >> (low % 8)) & ((1 << len) - 1);
+        value << (low as isize - start)
+    }
+}
+
+/// Finite state entropy decoding table
+#[derive(Clone)]
+struct Fse {
+    accuracy: u32,
+    symbols: Vec<u8>,
+    bits: Vec<u8>,
+    base: Vec<u16>,
+}
+
+impl Fse {
+    /// Table spreading the normalized `probabilities` of the symbols over `1 << accuracy` states
+    fn new(probabilities: &[i32], accuracy: u32) -> io::Result<Self> {
+        let size = 1usize << accuracy;
+        let mut symbols = vec![0u8; size];
+        let mut next = vec![0u32; probabilities.len()];
+
+        // Symbols less likely than one state get a single state, from the end of the table
+        let mut high = size;
+        for (symbol, &probability) in probabilities.iter().enumerate() {
+            if probability == -1 {
+                high = high
+                    .checked_sub(1)
+                    .ok_or_else(|| invalid("bad distribution"))?;
+                symbols[high] = symbol as u8;
+                next[symbol] = 1;
+            }
+        }
+
+        let step = (size >> 1) + (size >> 3) + 3;
+        let mut pos = 0;
+        for (symbol, &probability) in probabilities.iter().enumerate() {
+            if probability <= 0 {
+                continue;
+            }
+            next[symbol] = probability as u32;
+            for _ in 0..probability {
+                symbols[pos] = symbol as u8;
+                pos = (pos + step) & (size - 1);
+                while pos >= high {
+                    pos = (pos + step) & (size - 1);
+                }
+            }
+        }
+        if pos != 0 {
+            return Err(invalid("bad distribution"));
+        }
+
+        let mut bits = vec![0u8; size];
+        let mut base = vec![0u16; size];
+        for state in 0..size {
+            let desc = next[symbols[state] as usize];
+            next[symbols[state] as usize] += 1;
+            bits[state] = (accuracy - highest_bit(desc)) as u8;
+            base[state] = ((desc << bits[state]) as usize - size) as u16;
+        }
+        Ok(Fse {
+            accuracy,
+            symbols,
+            bits,
+            base,
+        })
+    }
+
+    /// Table which only ever decodes `symbol`
+    fn rle(symbol: u8) -> Self {
+        Fse {
+            accuracy: 0,
+            symbols: vec![symbol],
+            bits: vec![0],
+            base: vec![0],
+        }
+    }
+
+    /// Reads the description of a table at the start of `data`, returning its length as well
+    fn read(data: &[u8], max_accuracy: u32, max_symbol: usize) -> io::Result<(Self, usize)> {
+        let mut reader = ForwardBits { data, pos: 0 };
+        let accuracy = reader.read(4) + 5;
+        if accuracy > max_accuracy {
+            return Err(invalid("table accuracy too high"));
+        }
+
+        let mut remaining = 1i32 << accuracy;
+        let mut probabilities = Vec::new();
+        while remaining > 0 && probabilities.len() <= max_symbol {
+            let bits = highest_bit(remaining as u32 + 1) + 1;
+            let mut value = reader.read(bits) as i32;
+            let lower_mask = (1 << (bits - 1)) - 1;
+            let threshold = (1 << bits) - 1 - (remaining + 1);
+            if value & lower_mask < threshold {
+                // Small values take one bit less
+                reader.pos -= 1;
+                value &= lower_mask;
+            } else if value > lower_mask {
+                value -= threshold;
+            }
+            let probability = value - 1;
+            remaining -= probability.abs();
+            probabilities.push(probability);
+
+            if probability == 0 {
+                loop {
+                    let repeat = reader.read(2);
+                    probabilities.extend((0..repeat).map(|_| 0));
+                    if repeat != 3 {
+                        break;
+                    }
+                }
+            }
+        }
+        if remaining != 0 || probabilities.len() > max_symbol + 1 {
+            return Err(invalid("bad distribution"));
+        }
+        Ok((Fse::new(&probabilities, accuracy)?, reader.bytes()?))
+    }
+
+    fn symbol(&self, state: usize) -> u8 {
+        self.symbols[state]
+    }
+
+    fn next(&self, state: usize, bits: &mut BackwardBits) -> usize {
+        self.base[state] as usize + bits.read(self.bits[state] as u32) as usize
+    }
+}
+
+/// Huffman decoding table of literals, indexed by the next `max_bits` bits of a stream
+struct Huffman {
+    max_bits: u32,
+    symbols: Vec<u8>,
+    bits: Vec<u8>,
+}
+
+impl Huffman {
+    /// Reads the description of a table at the start of `data`, returning its length as well
+    fn read(data: &[u8]) -> io::Result<(Self, usize)> {
+        let header = *data.first().ok_or_else(|| invalid("block ends early"))? as usize;
+        let mut weights = Vec::new();
+        let len = match header >= 128 {
+            // Weights of four bits each
+            true => {
+                let count = header - 127;
+                let packed = slice(data, 1, count.div_ceil(2))?;
+                for index in 0..count {
+                    weights.push(match index % 2 {
+                        0 => packed[index / 2] >> 4,
+                        _ => packed[index / 2] & 0x0f,
+                    });
+                }
+                1 + packed.len()
+            }
+            // Weights coded with two interleaved states of a table
+            false => {
+                let coded = slice(data, 1, header)?;
+                let (fse, used) = Fse::read(coded, 6, 255)?;
+                let mut bits = BackwardBits::new(&coded[used..])?;
+                let mut states = [0, 0].map(|_| bits.read(fse.accuracy) as usize);
+                'decode: loop {
+                    for turn in 0..2 {
+                        weights.push(fse.symbol(states[turn]));
+                        states[turn] = fse.next(states[turn], &mut bits);
+                        if bits.left < 0 {
+                            weights.push(fse.symbol(states[1 - turn]));
+                            break 'decode;
+                        }
+                    }
+                    if weights.len() > 255 {
+                        return Err(invalid("too many Huffman weights"));
+                    }
+                }
+                1 + header
+            }
+        };
+
+        // The weight of the last symbol is left out, as the others tell what it must be
+        let total: u32 = weights
+            .iter()
+            .filter(|&&weight| weight > 0)
+            .map(|&weight| 1 << (weight - 1))
+            .sum();
+        if total == 0 || weights.len() > 255 {
+            return Err(invalid("bad Huffman weights"));
+        }
+        let max_bits = highest_bit(total) + 1;
+        let left = (1 << max_bits) - total;
+        if max_bits > MAX_HUFFMAN_BITS || !left.is_power_of_two() {
+            return Err(invalid("bad Huffman weights"));
+        }
+        weights.push(highest_bit(left) as u8 + 1);
+
+        let lengths: Vec<u32> = weights
+            .iter()
+            .map(|&weight| match weight {
+                0 => 0,
+                _ => max_bits + 1 - weight as u32,
+            })
+            .collect();
+        let size = 1usize << max_bits;
+        let mut counts = [0usize; MAX_HUFFMAN_BITS as usize + 1];
+        for &length in &lengths {
+            counts[length as usize] += 1;
+        }
+        // Codes of the same length take up states next to each other, longest codes first
+        let mut starts = [0usize; MAX_HUFFMAN_BITS as usize + 1];
+        let mut symbols = vec![0u8; size];
+        let mut bits = vec![0u8; size];
+        let mut start = 0;
+        for length in (1..=max_bits as usize).rev() {
+            starts[length] = start;
+            let end = start + (counts[length] << (max_bits as usize - length));
+            bits.get_mut(start..end)
+                .ok_or_else(|| invalid("bad Huffman weights"))?
+                .fill(length as u8);
+            start = end;
+        }
+        if start != size {
+            return Err(invalid("bad Huffman weights"));
+        }
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length > 0 {
+                let span = 1 << (max_bits - length);
+                let start = starts[length as usize];
+                symbols[start..start + span].fill(symbol as u8);
+                starts[length as usize] += span;
+            }
+        }
+        Ok((
+            Huffman {
+                max_bits,
+                symbols,
+                bits,
+            },
+            len,
+        ))
+    }
+
+    /// Decodes a whole stream of literals onto `out`
+    fn decode(&self, stream: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        let mut bits = BackwardBits::new(stream)?;
+        let mask = (1 << self.max_bits) - 1;
+        let mut state = bits.read(self.max_bits) as usize;
+        while bits.left > -(self.max_bits as isize) {
+            out.push(self.symbols[state]);
+            let len = self.bits[state] as u32;
+            state = ((state << len) + bits.read(len) as usize) & mask;
+        }
+        match bits.left == -(self.max_bits as isize) {
+            true => Ok(()),
+            false => Err(invalid("bad literals stream")),
+        }
+    }
+}
+
+/// Decoder for zstd frames (RFC 8878), which may be concatenated or mixed with skippable frames
+pub struct ZstdDecoder<R> {
+    reader: R,
+    /// The output of the frame, as far back as its window reaches
+    history: Vec<u8>,
+    /// Start of the output in `history` not yet read
+    pos: usize,
+    window_size: usize,
+    checksum: Option<Xxh64>,
+    /// Whether the last block of the frame is yet to come
+    in_frame: bool,
+    finished: bool,
+    /// Tables a block may take over from the blocks before it in the frame
+    huffman: Option<Huffman>,
+    tables: [Option<Fse>; 3],
+    offsets: [usize; 3],
+}
+
+impl<R: BufRead> ZstdDecoder<R> {
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut decoder = ZstdDecoder {
+            reader,
+            history: Vec::new(),
+            pos: 0,
+            window_size: 0,
+            checksum: None,
+            in_frame: false,
+            finished: false,
+            huffman: None,
+            tables: [None, None, None],
+            offsets: [1, 4, 8],
+        };
+        if decoder.reader.fill_buf()?.is_empty() {
+            return Err(invalid("no frame"));
+        }
+        decoder.next_frame()?;
+        Ok(decoder)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> io::Result<Vec<u8>> {
+        let mut bytes = vec![0u8; len];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn read_le(&mut self, len: usize) -> io::Result<u64> {
+        Ok(self
+            .read_bytes(len)?
+            .iter()
+            .rev()
+            .fold(0, |value, &byte| (value << 8) | byte as u64))
+    }
+
+    /// Reads the header of the next frame past any skippable ones, unless the input ends
+    fn next_frame(&mut self) -> io::Result<()> {
+        loop {
+            if self.reader.fill_buf()?.is_empty() {
+                self.finished = true;
+                return Ok(());
+            }
+            let magic = self.read_le(4)? as u32;
+            if magic & !0x0f == SKIPPABLE_MAGIC {
+                let len = self.read_le(4)?;
+                if io::copy(&mut (&mut self.reader).take(len), &mut io::sink())? < len {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                continue;
+            }
+            if magic != MAGIC {
+                return Err(invalid("bad magic bytes"));
+            }
+            break;
+        }
+
+        let descriptor = self.read_le(1)? as u8;
+        let single_segment = descriptor & 0x20 != 0;
+        if descriptor & 0x08 != 0 {
+            return Err(invalid("reserved frame header bit set"));
+        }
+        let mut window_size = match single_segment {
+            true => 0,
+            false => {
+                let byte = self.read_le(1)?;
+                let base = 1u64 << (10 + (byte >> 3));
+                base + (base / 8) * (byte & 0x07)
+            }
+        };
+        let dictionary = self.read_le([0, 1, 2, 4][(descriptor & 0x03) as usize])?;
+        if dictionary != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "zstd frames needing a dictionary aren't supported",
+            ));
+        }
+        let content_size = match descriptor >> 6 {
+            0 => self.read_le(single_segment as usize)?,
+            1 => self.read_le(2)? + 256,
+            2 => self.read_le(4)?,
+            _ => self.read_le(8)?,
+        };
+        if single_segment {
+            window_size = content_size;
+        }
+        if window_size > MAX_WINDOW_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "zstd frames with a window beyond 2 GiB aren't supported",
+            ));
+        }
+
+        self.window_size = window_size as usize;
+        self.checksum = (descriptor & 0x04 != 0).then(Xxh64::new);
+        self.in_frame = true;
+        self.history.clear();
+        self.pos = 0;
+        self.huffman = None;
+        self.tables = [None, None, None];
+        self.offsets = [1, 4, 8];
+        Ok(())
+    }
+
+    /// Decodes the next block onto `history`, checking the frame once its last block is done
+    fn next_block(&mut self) -> io::Result<()> {
+        let header = self.read_le(3)? as usize;
+        let last = header & 1 != 0;
+        let len = header >> 3;
+        if len > MAX_BLOCK_SIZE {
+            return Err(invalid("block too large"));
+        }
+        let start = self.history.len();
+        match (header >> 1) & 3 {
+            0 => {
+                let bytes = self.read_bytes(len)?;
+                self.history.extend_from_slice(&bytes);
+            }
+            1 => {
+                let byte = self.read_le(1)? as u8;
+                self.history.resize(start + len, byte);
+            }
+            2 => {
+                let content = self.read_bytes(len)?;
+                self.compressed_block(&content)?;
+            }
+            _ => return Err(invalid("reserved block type")),
+        }
+        if let Some(checksum) = &mut self.checksum {
+            checksum.update(&self.history[start..]);
+        }
+
+        if last {
+            self.in_frame = false;
+            if let Some(checksum) = self.checksum.take() {
+                if self.read_le(4)? as u32 != checksum.finish() as u32 {
+                    return Err(invalid("checksum mismatch"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the literals section at the start of a compressed block, returning its length
+    fn literals(&mut self, content: &[u8]) -> io::Result<(Vec<u8>, usize)> {
+        let header = slice(content, 0, 5.min(content.len()))?;
+        let byte = |index: usize| header.get(index).map_or(0, |&byte| byte as usize);
+        let kind = byte(0) & 3;
+        let format = (byte(0) >> 2) & 3;
+
+        if kind < 2 {
+            let (size, header_len) = match format {
+                0 | 2 => (byte(0) >> 3, 1),
+                1 => ((byte(0) >> 4) | (byte(1) << 4), 2),
+                _ => ((byte(0) >> 4) | (byte(1) << 4) | (byte(2) << 12), 3),
+            };
+            return match kind {
+                0 => Ok((
+                    slice(content, header_len, size)?.to_vec(),
+                    header_len + size,
+                )),
+                _ => Ok((
+                    vec![slice(content, header_len, 1)?[0]; size],
+                    header_len + 1,
+                )),
+            };
+        }
+
+        let (streams, size, compressed, header_len) = match format {
+            0 | 1 => {
+                let size = (byte(0) >> 4) | ((byte(1) & 0x3f) << 4);
+                let compressed = (byte(1) >> 6) | (byte(2) << 2);
+                (if format == 0 { 1 } else { 4 }, size, compressed, 3)
+            }
+            2 => {
+                let size = (byte(0) >> 4) | (byte(1) << 4) | ((byte(2) & 0x03) << 12);
+                let compressed = (byte(2) >> 2) | (byte(3) << 6);
+                (4, size, compressed, 4)
+            }
+            _ => {
+                let size = (byte(0) >> 4) | (byte(1) << 4) | ((byte(2) & 0x3f) << 12);
+                let compressed = (byte(2) >> 6) | (byte(3) << 2) | (byte(4) << 10);
+                (4, size, compressed, 5)
+            }
+        };
+        let data = slice(content, header_len, compressed)?;
+        let data = match kind {
+            2 => {
+                let (huffman, len) = Huffman::read(data)?;
+                self.huffman = Some(huffman);
+                &data[len..]
+            }
+            _ => data,
+        };
+        let huffman = self
+            .huffman
+            .as_ref()
+            .ok_or_else(|| invalid("literals reuse a Huffman table never given"))?;
+
+        let mut literals = Vec::with_capacity(size);
+        match streams {
+            1 => huffman.decode(data, &mut literals)?,
+            _ => {
+                let jumps = slice(data, 0, 6)?;
+                let mut start = 6;
+                for index in 0..4 {
+                    let len = match index {
+                        3 => data
+                            .len()
+                            .checked_sub(start)
+                            .ok_or_else(|| invalid("bad jump table"))?,
+                        _ => u16::from_le_bytes([jumps[2 * index], jumps[2 * index + 1]]) as usize,
+                    };
+                    huffman.decode(slice(data, start, len)?, &mut literals)?;
+                    start += len;
+                }
+            }
+        }
+        match literals.len() == size {
+            true => Ok((literals, header_len + compressed)),
+            false => Err(invalid("wrong number of literals")),
+        }
+    }
+
+    /// Table of a sequences section given by its `mode`, which may follow at the start of `data`
+    fn table(
+        &mut self,
+        index: usize,
+        mode: u8,
+        data: &[u8],
+        (default_accuracy, max_accuracy, max_symbol): (u32, u32, usize),
+        default: &[i32],
+    ) -> io::Result<usize> {
+        let (table, len) = match mode {
+            0 => (Fse::new(default, default_accuracy)?, 0),
+            1 => (Fse::rle(slice(data, 0, 1)?[0]), 1),
+            2 => Fse::read(data, max_accuracy, max_symbol)?,
+            _ => (
+                self.tables[index]
+                    .clone()
+                    .ok_or_else(|| invalid("sequences reuse a table never given"))?,
+                0,
+            ),
+        };
+        self.tables[index] = Some(table);
+        Ok(len)
+    }
+
+    /// Offset of a sequence from its coded value, keeping the three most recent ones up to date
+    fn offset(&mut self, value: usize, literals_len: usize) -> usize {
+        if value > 3 {
+            self.offsets = [value - 3, self.offsets[0], self.offsets[1]];
+            return value - 3;
+        }
+        let index = value - 1 + (literals_len == 0) as usize;
+        if index == 0 {
+            return self.offsets[0];
+        }
+        let offset = match index {
+            3 => self.offsets[0].saturating_sub(1),
+            _ => self.offsets[index],
+        };
+        if index > 1 {
+            self.offsets[2] = self.offsets[1];
+        }
+        self.offsets[1] = self.offsets[0];
+        self.offsets[0] = offset;
+        offset
+    }
+
+    fn compressed_block(&mut self, content: &[u8]) -> io::Result<()> {
+        let (literals, used) = self.literals(content)?;
+        let mut data = &content[used..];
+
+        let byte = |data: &[u8], index: usize| slice(data, index, 1).map(|byte| byte[0] as usize);
+        let (count, header_len) = match byte(data, 0)? {
+            0 => (0, 1),
+            first @ 1..128 => (first, 1),
+            first @ 128..255 => (((first - 128) << 8) + byte(data, 1)?, 2),
+            _ => (byte(data, 1)? + (byte(data, 2)? << 8) + 0x7f00, 3),
+        };
+        data = &data[header_len..];
+
+        let mut sequences = Vec::with_capacity(count);
+        if count > 0 {
+            let modes = byte(data, 0)? as u8;
+            if modes & 0x03 != 0 {
+                return Err(invalid("reserved sequence modes set"));
+            }
+            data = &data[1..];
+            let len = self.table(0, modes >> 6, data, LL_TABLE, &LL_DEFAULT)?;
+            data = &data[len..];
+            let len = self.table(1, (modes >> 4) & 3, data, OF_TABLE, &OF_DEFAULT)?;
+            data = &data[len..];
+            let len = self.table(2, (modes >> 2) & 3, data, ML_TABLE, &ML_DEFAULT)?;
+            data = &data[len..];
+
+            let tables = std::mem::take(&mut self.tables);
+            let [Some(ll), Some(of), Some(ml)] = &tables else {
+                unreachable!("every table was just set")
+            };
+            let mut bits = BackwardBits::new(data)?;
+            let mut ll_state = bits.read(ll.accuracy) as usize;
+            let mut of_state = bits.read(of.accuracy) as usize;
+            let mut ml_state = bits.read(ml.accuracy) as usize;
+            for index in 0..count {
+                let ll_code = ll.symbol(ll_state) as usize;
+                let of_code = of.symbol(of_state) as u32;
+                let ml_code = ml.symbol(ml_state) as usize;
+                if ll_code >= LL_BASE.len() || ml_code >= ML_BASE.len() || of_code > 31 {
+                    return Err(invalid("bad sequence code"));
+                }
+                let offset = (1 << of_code) + bits.read(of_code) as usize;
+                let match_len = (ML_BASE[ml_code] as u64 + bits.read(ML_BITS[ml_code])) as usize;
+                let literals_len = (LL_BASE[ll_code] as u64 + bits.read(LL_BITS[ll_code])) as usize;
+                if index + 1 < count {
+                    ll_state = ll.next(ll_state, &mut bits);
+                    ml_state = ml.next(ml_state, &mut bits);
+                    of_state = of.next(of_state, &mut bits);
+                }
+                let offset = self.offset(offset, literals_len);
+                sequences.push((literals_len, offset, match_len));
+            }
+            self.tables = tables;
+            if bits.left != 0 {
+                return Err(invalid("bad sequences stream"));
+            }
+        }
+
+        let mut rest = literals.as_slice();
+        for (literals_len, offset, match_len) in sequences {
+            let (copied, later) = rest
+                .split_at_checked(literals_len)
+                .ok_or_else(|| invalid("sequence takes more literals than there are"))?;
+            self.history.extend_from_slice(copied);
+            rest = later;
+
+            let start = self
+                .history
+                .len()
+                .checked_sub(offset)
+                .filter(|_| offset > 0)
+                .ok_or_else(|| invalid("match reaches before the frame"))?;
+            match offset >= match_len {
+                true => self.history.extend_from_within(start..start + match_len),
+                false => {
+                    for index in start..start + match_len {
+                        self.history.push(self.history[index]);
+                    }
+                }
+            }
+        }
+        self.history.extend_from_slice(rest);
+        Ok(())
+    }
+
+    /// Forgets the output the window no longer reaches, once it has all been read
+    fn trim(&mut self) {
+        let keep = self.window_size.max(MAX_BLOCK_SIZE);
+        if self.history.len() > 2 * keep {
+            self.history.drain(..self.history.len() - keep);
+            self.pos = self.history.len();
+        }
+    }
+}
+
+impl<R: BufRead> Read for ZstdDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.history.len() && !self.finished {
+            self.trim();
+            match self.in_frame {
+                true => self.next_block()?,
+                false => self.next_frame()?,
+            }
+        }
+
+        let len = buf.len().min(self.history.len() - self.pos);
+        buf[..len].copy_from_slice(&self.history[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, read_all};
+
+    const SAMPLE: &[u8] = include_bytes!("../tests/fixtures/sample.zst");
+    const NOISE: &[u8] = include_bytes!("../tests/fixtures/noise.zst");
+    const ZEROS: &[u8] = include_bytes!("../tests/fixtures/zeros.zst");
+
+    fn decode(data: &[u8]) -> io::Result<Vec<u8>> {
+        read_all(ZstdDecoder::new(data)?)
+    }
+
+    #[test]
+    fn decodes_compressed_blocks_and_checks_them() {
+        assert_eq!(decode(SAMPLE).unwrap(), fixtures::sample());
+    }
+
+    #[test]
+    fn decodes_raw_blocks() {
+        assert_eq!(decode(NOISE).unwrap(), fixtures::noise(4096));
+    }
+
+    #[test]
+    fn decodes_rle_blocks() {
+        assert_eq!(decode(ZEROS).unwrap(), vec![0; 300_000]);
+    }
+
+    #[test]
+    fn skips_skippable_frames_between_frames() {
+        let skippable = [0x50, 0x2a, 0x4d, 0x18, 3, 0, 0, 0, 1, 2, 3];
+        let data = [&skippable, NOISE, &skippable, ZEROS].concat();
+        let mut expected = fixtures::noise(4096);
+        expected.resize(4096 + 300_000, 0);
+        assert_eq!(decode(&data).unwrap(), expected);
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        for fixture in [SAMPLE, NOISE, ZEROS] {
+            for data in fixtures::truncations(fixture, 61) {
+                assert!(decode(data).is_err(), "{} bytes", data.len());
+            }
+        }
+    }
+
+    #[test]
+    fn corrupt_data_is_an_error() {
+        for data in fixtures::corruptions(SAMPLE, 61) {
+            assert!(decode(&data).is_err());
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const HEX_THING: &str = env!("CARGO_BIN_EXE_hex-thing");
+
+/// Text columns of the dump of `input` with `args`
+fn text_columns(input: &[u8], args: &[&str]) -> Vec<String> {
+    let mut child = Command::new(HEX_THING)
+        .args(["-", "--color", "never"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| line.rsplit(" │ ").next().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn character_across_lines_shows_where_it_starts() {
+    let input = "aé€é€xyz".as_bytes();
+    let columns = text_columns(input, &["--charset", "utf8", "-l", "5"]);
+    assert_eq!(columns, ["aé·€·", "·é·€·", "·xyz"]);
+}
+
+#[test]
+fn wide_character_across_lines_and_splits_shows_where_it_starts() {
+    let input = b"a\x82\xa0\x82\xa0\x82\xa0b";
+    let columns = text_columns(input, &["--charset", "shift-jis", "-l", "4"]);
+    assert_eq!(columns, ["aああ", "·あb"]);
+    let columns = text_columns(input, &["--charset", "shift-jis", "--split-every", "3"]);
+    assert_eq!(columns, ["aあ│ああ│b"]);
+}
@@ -0,0 +1,53 @@
+#!/usr/bin/env python3
+"""Generates the compressed fixtures of the decoder tests with gzip, xz and zstd.
+
+The data they hold is rebuilt by `fixtures::sample` and `fixtures::noise` in the tests.
+
+    python3 tests/fixtures/generate.py
+"""
+
+import subprocess
+from pathlib import Path
+
+
+def sample():
+    """Lines of text past a window of history, then noise and zeros"""
+    text = b"".join(b"%d: the quick brown fox jumps over the lazy dog\n" % i for i in range(3000))
+    return text + noise(4096) + bytes(4096)
+
+
+def noise(len):
+    """Bytes of a 64-bit linear congruential generator, which don't compress"""
+    state, data = 1, bytearray()
+    for _ in range(len):
+        state = (state * 6364136223846793005 + 1442695040888963407) % 2**64
+        data.append(state >> 56)
+    return bytes(data)
+
+
+def compress(args, data):
+    return subprocess.run(args, input=data, stdout=subprocess.PIPE, check=True).stdout
+
+
+if __name__ == "__main__":
+    directory = Path(__file__).parent
+    short = b"hello, world\n"
+    fixtures = {
+        # Dynamic blocks, a stored one for the noise, and fixed codes for a short member
+        "sample.gz": compress(["gzip", "-9n"], sample()),
+        "noise.gz": compress(["gzip", "-9n"], noise(4096)),
+        "members.gz": compress(["gzip", "-n"], short) + compress(["gzip", "-n"], sample()),
+        "sample.lzma2": compress(["xz", "--format=raw", "--lzma2=dict=64KiB"], sample()),
+        "blocks.xz": compress(["xz", "--block-size=32KiB"], sample()),
+        "crc32.xz": compress(["xz", "--check=crc32"], sample()),
+        "sha256.xz": compress(["xz", "--check=sha256"], short),
+        "streams.xz": compress(["xz", "--check=none"], short) + compress(["xz"], sample()),
+        "delta.xz": compress(["xz", "--filters=delta--lzma2"], sample()),
+        "x86.xz": compress(["xz", "--x86", "--lzma2"], sample()),
+        # Blocks of every kind: compressed ones, raw for the noise and RLE for the zeros
+        "sample.zst": compress(["zstd", "-19", "--check", "--no-progress"], sample()),
+        "noise.zst": compress(["zstd", "--no-check", "--no-progress"], noise(4096)),
+        "zeros.zst": compress(["zstd", "--no-progress"], bytes(300_000)),
+    }
+    for name, data in fixtures.items():
+        (directory / name).write_bytes(data)
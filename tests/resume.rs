@@ -0,0 +1,90 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const HEX_THING: &str = env!("CARGO_BIN_EXE_hex-thing");
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("hex-thing-resume-{}-{name}", std::process::id()))
+}
+
+fn sample() -> Vec<u8> {
+    (0..50_000u32)
+        .map(|index| (index * 31 % 251) as u8)
+        .collect()
+}
+
+/// Dumps `input` through standard input, as uninterrupted dumps and resumed ones both read it
+fn dump(input: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(HEX_THING)
+        .arg("-")
+        .args(args)
+        .stdin(fs::File::open(input).unwrap())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn resumed_dump_matches_uninterrupted_dump() {
+    let data = sample();
+    let input = temp_path("input");
+    let expected = temp_path("expected");
+    let output = temp_path("output");
+    let state = temp_path("state");
+    fs::write(&input, &data).unwrap();
+    let expected_arg = expected.to_str().unwrap();
+    assert!(dump(&input, &["-o", expected_arg]).status.success());
+
+    // Half of the input, then a line more once progress is due to be saved, and the dump
+    // is killed as soon as it was
+    let output_arg = output.to_str().unwrap();
+    let state_arg = state.to_str().unwrap();
+    let mut child = Command::new(HEX_THING)
+        .args(["-", "-o", output_arg, "--resume", state_arg])
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(&data[..data.len() / 2]).unwrap();
+    stdin.flush().unwrap();
+    thread::sleep(Duration::from_millis(1500));
+    stdin.write_all(&data[data.len() / 2..][..16]).unwrap();
+    stdin.flush().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !state.exists() {
+        assert!(Instant::now() < deadline, "no progress was saved");
+        thread::sleep(Duration::from_millis(10));
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    drop(stdin);
+    assert!(fs::read(&output).unwrap().len() < fs::read(&expected).unwrap().len());
+
+    let resumed = dump(&input, &["-o", output_arg, "--resume", state_arg]);
+    assert!(resumed.status.success());
+    assert_eq!(fs::read(&output).unwrap(), fs::read(&expected).unwrap());
+    assert!(!state.exists());
+
+    for path in [input, expected, output] {
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn resume_rejects_formats_written_at_the_end() {
+    let input = temp_path("formats");
+    fs::write(&input, sample()).unwrap();
+    for format in ["json", "html", "svg", "escaped", "ihex", "srec", "uf2"] {
+        let state = temp_path(&format!("{format}-state"));
+        let result = dump(
+            &input,
+            &["--format", format, "--resume", state.to_str().unwrap()],
+        );
+        assert!(!result.status.success(), "--format {format} was resumable");
+        assert!(!state.exists());
+    }
+    fs::remove_file(input).unwrap();
+}
@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+const HEX_THING: &str = env!("CARGO_BIN_EXE_hex-thing");
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("hex-thing-reverse-{}-{name}", std::process::id()))
+}
+
+/// Intel HEX data record of `data` at `address`
+fn ihex_record(address: u16, data: &[u8]) -> String {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend_from_slice(&address.to_be_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+    bytes.push(sum.wrapping_neg());
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02X}")).collect();
+    format!(":{hex}\n")
+}
+
+#[test]
+fn reversed_dump_of_ihex_with_gap_keeps_fill_bytes() {
+    let first = [0x41u8; 16];
+    let second = [0x00u8, 0x42, 0x00, 0x42];
+    let text = ihex_record(0, &first) + &ihex_record(0x1000, &second) + ":00000001FF\n";
+    let mut expected = first.to_vec();
+    expected.resize(0x1000, 0xff);
+    expected.extend_from_slice(&second);
+
+    let input = temp_path("input.hex");
+    let dump = temp_path("dump.txt");
+    let output = temp_path("output.bin");
+    fs::write(&input, text).unwrap();
+
+    let status = Command::new(HEX_THING)
+        .args(["--input-format", "ihex"])
+        .arg(&input)
+        .arg("-o")
+        .arg(&dump)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(fs::read_to_string(&dump).unwrap().contains("gap: "));
+
+    let status = Command::new(HEX_THING)
+        .arg("-r")
+        .arg(&dump)
+        .arg("-o")
+        .arg(&output)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(fs::read(&output).unwrap(), expected);
+
+    for path in [input, dump, output] {
+        fs::remove_file(path).unwrap();
+    }
+}
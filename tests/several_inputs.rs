@@ -0,0 +1,37 @@
+use std::fs;
+use std::process::Command;
+
+const HEX_THING: &str = env!("CARGO_BIN_EXE_hex-thing");
+
+/// Formats whose output is a single document, which can't hold a second input
+const SINGLE: &[&str] = &["html", "svg", "ihex", "srec", "uf2"];
+
+#[test]
+fn single_document_formats_reject_several_inputs() {
+    let first = std::env::temp_dir().join(format!("hex-thing-several-{}-a", std::process::id()));
+    let second = std::env::temp_dir().join(format!("hex-thing-several-{}-b", std::process::id()));
+    fs::write(&first, b"first").unwrap();
+    fs::write(&second, b"second").unwrap();
+    for format in SINGLE {
+        let output = Command::new(HEX_THING)
+            .args(["--format", format])
+            .args([&first, &second])
+            .output()
+            .unwrap();
+        assert!(
+            !output.status.success(),
+            "--format {format} took several inputs"
+        );
+        assert!(output.stdout.is_empty());
+    }
+    for format in SINGLE {
+        let output = Command::new(HEX_THING)
+            .args(["--format", format])
+            .arg(&first)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    }
+    fs::remove_file(first).unwrap();
+    fs::remove_file(second).unwrap();
+}